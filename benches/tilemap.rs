@@ -0,0 +1,50 @@
+//! 网格占用表与逐个遍历墙体实体的通行判断对比（101x101 的随机地图）
+
+use bevy::prelude::*;
+use bomberman::{
+    components::{BreakableWall, GridPosition, Wall},
+    grid::cells,
+    level::Tile,
+    mapgen::{generate, MapGenConfig},
+    tilemap::{Occupant, TileMap},
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const SIZE: usize = 101;
+
+fn solid_lookup(c: &mut Criterion) {
+    let config = MapGenConfig { width: SIZE, height: SIZE, breakable_density: 0.5, enemies: 0 };
+    let level = generate(1, &config);
+
+    // 同一张地图的墙体实体，以及登记了这些实体的占用表
+    let mut world = World::new();
+    let mut tile_map = TileMap::new(level.width, level.height);
+    for pos in cells(level.width, level.height) {
+        let (entity, kind) = match level.tile(pos.x, pos.y) {
+            Tile::Wall => (world.spawn((Wall, pos)).id(), Occupant::Wall),
+            Tile::BreakableWall => (world.spawn((BreakableWall, pos)).id(), Occupant::BreakableWall),
+            Tile::Empty => continue,
+        };
+        tile_map.insert(entity, pos, kind);
+    }
+    let mut walls = world.query_filtered::<&GridPosition, Or<(With<Wall>, With<BreakableWall>)>>();
+    let targets: Vec<GridPosition> = cells(level.width, level.height).step_by(7).collect();
+
+    let mut group = c.benchmark_group("solid lookup");
+    // 引入占用表之前的做法：每次判断都遍历所有墙体
+    group.bench_function("linear scan", |b| {
+        b.iter(|| {
+            targets
+                .iter()
+                .filter(|target| walls.iter(&world).any(|pos| pos == *target))
+                .count()
+        })
+    });
+    group.bench_function("tile map", |b| {
+        b.iter(|| targets.iter().filter(|target| tile_map.is_solid(black_box(**target))).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, solid_lookup);
+criterion_main!(benches);
@@ -0,0 +1,194 @@
+use bevy::prelude::*;
+use crate::{
+    components::{Dying, SpriteFrame, Stop},
+    constants::*,
+    resource::{GamePaused, GameState},
+};
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system(animate_sprites.in_set(OnUpdate(GameState::InGame)))
+            .add_system(start_death_animation.in_set(OnUpdate(GameState::InGame)))
+            .add_system(despawn_dead.in_set(OnUpdate(GameState::InGame)));
+    }
+}
+
+/// 动画片段名称
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipName {
+    Idle,
+    WalkUp,
+    WalkDown,
+    WalkLeft,
+    WalkRight,
+    Death,
+    Fuse,
+}
+
+impl ClipName {
+    /// 根据移动方向选择行走片段（y 向下为正）
+    pub fn walk(dx: i32, dy: i32) -> Self {
+        match (dx, dy) {
+            (-1, _) => Self::WalkLeft,
+            (1, _) => Self::WalkRight,
+            (_, -1) => Self::WalkUp,
+            _ => Self::WalkDown,
+        }
+    }
+}
+
+/// 一段动画：图集帧序列和每帧时长
+#[derive(Clone, Debug)]
+pub struct AnimationClip {
+    pub frames: Vec<usize>,
+    pub frame_time: f32,
+    /// 播完后是否从头循环（否则停在最后一帧）
+    pub looping: bool,
+}
+
+impl AnimationClip {
+    pub fn new(frames: Vec<usize>, frame_time: f32, looping: bool) -> Self {
+        Self { frames, frame_time, looping }
+    }
+}
+
+/// 精灵动画组件：持有若干命名片段，由 animate_sprites 按计时器推进当前片段
+#[derive(Component, Clone, Debug)]
+pub struct SpriteAnimation {
+    clips: Vec<(ClipName, AnimationClip)>,
+    current: ClipName,
+    frame: usize,
+    timer: Timer,
+}
+
+impl SpriteAnimation {
+    pub fn new(clips: Vec<(ClipName, AnimationClip)>, initial: ClipName) -> Self {
+        let mut animation = Self {
+            clips,
+            current: initial,
+            frame: 0,
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+        };
+        animation.reset_timer();
+        animation
+    }
+
+    /// 角色动画（player.png / creature.png 的一行，每行14帧）
+    ///
+    /// 列布局：0~3 朝下行走，4~7 朝右行走，8~9 朝上行走，10~13 朝左行走。
+    /// 图集中没有死亡帧，死亡动画用四个朝向的站立帧转圈代替。
+    pub fn character(row: usize, idle_frame: usize) -> Self {
+        let base = row * 14;
+        let frames = |columns: &[usize]| columns.iter().map(|c| base + c).collect::<Vec<_>>();
+
+        Self::new(
+            vec![
+                (ClipName::Idle, AnimationClip::new(vec![base + idle_frame], WALK_FRAME_TIME, true)),
+                (ClipName::WalkDown, AnimationClip::new(frames(&[0, 1, 2, 3]), WALK_FRAME_TIME, true)),
+                (ClipName::WalkRight, AnimationClip::new(frames(&[4, 5, 6, 7]), WALK_FRAME_TIME, true)),
+                (ClipName::WalkUp, AnimationClip::new(frames(&[8, 9]), WALK_FRAME_TIME, true)),
+                (ClipName::WalkLeft, AnimationClip::new(frames(&[10, 11, 12, 13]), WALK_FRAME_TIME, true)),
+                (ClipName::Death, AnimationClip::new(frames(&[1, 4, 8, 10, 1, 4, 8, 10]), DEATH_FRAME_TIME, false)),
+            ],
+            ClipName::Idle,
+        )
+    }
+
+    /// 炸弹引信动画（bomb.png 的3帧）
+    pub fn bomb_fuse() -> Self {
+        Self::new(
+            vec![(ClipName::Fuse, AnimationClip::new(vec![0, 1, 2], FUSE_FRAME_TIME, true))],
+            ClipName::Fuse,
+        )
+    }
+
+    /// 切换片段（已在播放时不重置，避免每帧从头开始）
+    pub fn play(&mut self, name: ClipName) {
+        if self.current == name || self.clip(name).is_none() {
+            return;
+        }
+        self.current = name;
+        self.frame = 0;
+        self.reset_timer();
+    }
+
+    /// 非循环片段是否已播完
+    pub fn finished(&self) -> bool {
+        self.clip(self.current)
+            .map_or(true, |clip| !clip.looping && self.frame + 1 >= clip.frames.len())
+    }
+
+    /// 当前应显示的图集帧
+    pub fn sprite_index(&self) -> Option<usize> {
+        self.clip(self.current).and_then(|clip| clip.frames.get(self.frame).copied())
+    }
+
+    pub fn current(&self) -> ClipName {
+        self.current
+    }
+
+    fn clip(&self, name: ClipName) -> Option<&AnimationClip> {
+        self.clips.iter().find(|(n, _)| *n == name).map(|(_, clip)| clip)
+    }
+
+    fn reset_timer(&mut self) {
+        let frame_time = self.clip(self.current).map_or(1.0, |clip| clip.frame_time);
+        self.timer = Timer::from_seconds(frame_time, TimerMode::Repeating);
+    }
+
+    /// 推进动画，返回当前帧
+    fn advance(&mut self, delta: std::time::Duration) -> Option<usize> {
+        self.timer.tick(delta);
+        let steps = self.timer.times_finished_this_tick() as usize;
+        if let Some(clip) = self.clip(self.current) {
+            let len = clip.frames.len();
+            self.frame = if clip.looping {
+                (self.frame + steps) % len
+            } else {
+                (self.frame + steps).min(len - 1)
+            };
+        }
+        self.sprite_index()
+    }
+}
+
+/// 进入死亡状态的实体开始播放死亡动画
+fn start_death_animation(mut query: Query<&mut SpriteAnimation, Added<Dying>>) {
+    for mut animation in query.iter_mut() {
+        animation.play(ClipName::Death);
+    }
+}
+
+/// 死亡动画播完后移除实体（没有动画的实体立即移除）
+pub fn despawn_dead(
+    mut commands: Commands,
+    query: Query<(Entity, Option<&SpriteAnimation>), With<Dying>>,
+) {
+    for (entity, animation) in query.iter() {
+        if animation.map_or(true, |animation| animation.current() == ClipName::Death && animation.finished()) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// 推进所有精灵动画（暂停时和带 Stop 标记的实体不推进）
+pub fn animate_sprites(
+    time: Res<Time>,
+    game_paused: Res<GamePaused>,
+    mut query: Query<(&mut SpriteAnimation, &mut SpriteFrame), Without<Stop>>,
+) {
+    if game_paused.0 {
+        return;
+    }
+
+    for (mut animation, mut frame) in query.iter_mut() {
+        if let Some(index) = animation.advance(time.delta()) {
+            if frame.index != index {
+                frame.index = index;
+            }
+        }
+    }
+}
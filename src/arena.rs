@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use crate::{
+    components::*,
+    constants::*,
+    level::{ActiveLevel, Tile},
+    grid::grid_to_world,
+    map::{spawn_wall, spiral_cells},
+    resource::{ClosingArena, GamePaused, GameState, LevelTimer},
+    tilemap::{Occupant, TileMap, TileMapSet},
+};
+
+pub struct ArenaPlugin;
+
+impl Plugin for ArenaPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system(start_level_clock.in_schedule(OnEnter(GameState::InGame)))
+            .add_system(hurry_up.in_set(OnUpdate(GameState::InGame)))
+            .add_system(close_arena.in_set(OnUpdate(GameState::InGame)).after(TileMapSet::Sync));
+    }
+}
+
+/// 进入关卡：设置时间限制
+fn start_level_clock(mut commands: Commands, mut level_timer: ResMut<LevelTimer>, level: ActiveLevel) {
+    level_timer.limit = level.get().map_or(DEFAULT_TIME_LIMIT, |level| level.time_limit);
+    level_timer.hurry_up = false;
+    commands.remove_resource::<ClosingArena>();
+}
+
+/// 剩余时间不多时进入 HURRY UP：敌人变快（音乐由 SoundPlugin 加速）；时间耗尽后开始缩圈
+fn hurry_up(
+    mut commands: Commands,
+    mut level_timer: ResMut<LevelTimer>,
+    closing: Option<Res<ClosingArena>>,
+    level: ActiveLevel,
+    mut enemy_query: Query<&mut EnemyMoveTimer, With<Enemy>>,
+) {
+    if !level_timer.hurry_up && level_timer.remaining() <= HURRY_UP_TIME {
+        level_timer.hurry_up = true;
+        info!("hurry up!");
+
+        for mut move_timer in enemy_query.iter_mut() {
+            let interval = move_timer.timer.duration().mul_f32(HURRY_UP_ENEMY_INTERVAL_SCALE);
+            move_timer.timer.set_duration(interval);
+        }
+    }
+
+    if level_timer.remaining() <= 0.0 && closing.is_none() {
+        let Some(level) = level.get() else {
+            return;
+        };
+        commands.insert_resource(ClosingArena {
+            cells: spiral_cells(level.width, level.height),
+            next: 0,
+            timer: Timer::from_seconds(CLOSING_WALL_INTERVAL, TimerMode::Repeating),
+        });
+    }
+}
+
+/// 缩圈：按螺旋顺序逐格落下不可破坏墙，被压到的玩家和敌人死亡，格子上的炸弹和道具被清除
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn close_arena(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_paused: Res<GamePaused>,
+    closing: Option<ResMut<ClosingArena>>,
+    tile_map: Res<TileMap>,
+    mobile_query: Query<&GridPosition, (Or<(With<Player>, With<Enemy>)>, Without<Dying>)>,
+    bomb_query: Query<&Bomb>,
+    mut capacity_query: Query<&mut BombCapacity>,
+) {
+    let Some(mut closing) = closing else {
+        return;
+    };
+    if game_paused.0 {
+        return;
+    }
+
+    closing.timer.tick(time.delta());
+    for _ in 0..closing.timer.times_finished_this_tick() {
+        // 跳过已经是不可破坏墙的格子
+        let mut next_cell = None;
+        while let Some(&cell) = closing.cells.get(closing.next) {
+            closing.next += 1;
+            if tile_map.tile(cell) != Tile::Wall {
+                next_cell = Some(cell);
+                break;
+            }
+        }
+        let Some(cell) = next_cell else {
+            return;
+        };
+
+        for &(entity, occupant) in tile_map.occupants(cell) {
+            match occupant {
+                Occupant::BreakableWall => commands.entity(entity).despawn(),
+                Occupant::Player | Occupant::Enemy if mobile_query.get(entity).map_or(false, |pos| *pos == cell) => {
+                    commands.entity(entity).insert(Dying);
+                }
+                Occupant::Bomb => {
+                    commands.entity(entity).despawn();
+                    // 被压掉的炸弹归还给放置者
+                    if let Ok(mut capacity) = bomb_query.get(entity).and_then(|bomb| capacity_query.get_mut(bomb.owner)) {
+                        capacity.available = (capacity.available + 1).min(capacity.max);
+                    }
+                }
+                Occupant::PowerUp => commands.entity(entity).despawn_recursive(),
+                _ => {}
+            }
+        }
+
+        // 不可破坏墙 - 使用索引5
+        spawn_wall(&mut commands, cell.x, cell.y, grid_to_world(cell.x, cell.y), false, 5);
+    }
+}
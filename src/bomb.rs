@@ -1,9 +1,20 @@
-use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use crate::{
+    animation::SpriteAnimation,
     components::*,
     constants::*,
-    map::grid_to_world,
-    resource::{GameState, GameTextures, GameAudio, GameOverDelay},
+    level::{ActiveLevel, DropTable, Tile},
+    grid::{blast_arms, grid_to_world},
+    player::{input_axes, overlaps_cell, pushes_against},
+    powerup::spawn_power_up,
+    resource::{GameMode, GamePaused, GameState, LevelTimer, Lives, Score, GameOverDelay, MatchScore, SimRng},
+    sound::{Sound, SoundEvent},
+    tilemap::{Occupant, TileMap, TileMapSet},
 };
 
 pub struct BombPlugin;
@@ -11,218 +22,620 @@ pub struct BombPlugin;
 impl Plugin for BombPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_system(place_bomb.in_set(OnUpdate(GameState::InGame)))
-            .add_system(bomb_timer.in_set(OnUpdate(GameState::InGame)))
+            .add_event::<SoundEvent>()
+            .add_system(place_bomb.in_set(OnUpdate(GameState::InGame)).in_set(TileMapSet::Move))
+            .add_system(release_bomb_pass_through.in_set(OnUpdate(GameState::InGame)))
+            .add_system(kick_bombs.in_set(OnUpdate(GameState::InGame)).in_set(TileMapSet::Move))
+            .add_system(slide_bombs.in_set(OnUpdate(GameState::InGame)).in_set(TileMapSet::Move))
+            .add_system(remote_detonate.in_set(OnUpdate(GameState::InGame)))
+            .add_system(bomb_timer.in_set(OnUpdate(GameState::InGame)).after(TileMapSet::Sync))
             .add_system(explosion_timer.in_set(OnUpdate(GameState::InGame)))
+            .add_system(fire_hazard.in_set(OnUpdate(GameState::InGame)).after(TileMapSet::Sync))
             .add_system(check_game_over.in_set(OnUpdate(GameState::InGame)))
             .add_system(game_over_delay_timer.in_set(OnUpdate(GameState::InGame)));
     }
 }
 
-/// 放置炸弹（使用TextureAtlasSprite）
+/// 放置炸弹，每位玩家使用自己的按键
+///
+/// 拥有一排炸弹能力时，沿面朝方向一次放下所有剩余的炸弹（脚下已有炸弹时从前方一格开始）。
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn place_bomb(
     mut commands: Commands,
     keyboard: Res<Input<KeyCode>>,
-    game_textures: Res<GameTextures>,
-    game_audio: Res<GameAudio>,
-    audio: Res<Audio>,
-    player_query: Query<&GridPosition, (With<Player>, Without<Stop>)>,
-    bomb_query: Query<&GridPosition, With<Bomb>>,
+    mut sounds: EventWriter<SoundEvent>,
+    level: ActiveLevel,
+    mut next_order: Local<u64>,
+    mut player_query: Query<
+        (
+            Entity,
+            &GridPosition,
+            &Facing,
+            &BlastRange,
+            &mut BombCapacity,
+            &PlayerControls,
+            Option<&RemoteDetonator>,
+            Option<&PierceBombs>,
+            Option<&LineBomb>,
+        ),
+        (With<Player>, Without<Stop>, Without<Dying>)
+    >,
+    tile_map: Res<TileMap>,
+    enemy_query: Query<(), (With<Enemy>, Without<Dying>)>,
+    body_query: Query<(Entity, &Transform), (With<Player>, Without<Dying>)>,
+) {
+    let Some(level) = level.get() else {
+        return;
+    };
+
+    // 本帧刚放置的炸弹还不在占用表中（多名玩家可能站在同一格）
+    let mut placed: HashSet<GridPosition> = HashSet::new();
+    let occupied = |placed: &HashSet<GridPosition>, pos: &GridPosition| {
+        placed.contains(pos) || tile_map.contains(*pos, Occupant::Bomb)
+    };
+    let obstructed = |pos: &GridPosition| {
+        tile_map.is_solid(*pos) || tile_map.entities(*pos, Occupant::Enemy).any(|enemy| enemy_query.contains(enemy))
+    };
+
+    for (player_entity, player_pos, facing, blast_range, mut capacity, controls, remote, pierce, line) in
+        player_query.iter_mut()
+    {
+        if !PlayerControls::just_pressed(&keyboard, controls.bomb) {
+            continue;
+        }
+
+        // 要放置炸弹的格子：脚下，以及一排炸弹时面朝方向上的后续格子
+        let mut cells = Vec::new();
+        if !occupied(&placed, player_pos) {
+            cells.push(*player_pos);
+        }
+        if line.is_some() {
+            let mut pos = *player_pos;
+            while cells.len() < capacity.available as usize {
+                pos = pos.step((facing.x, facing.y));
+                if obstructed(&pos) || occupied(&placed, &pos) {
+                    break;
+                }
+                cells.push(pos);
+            }
+        }
+        // 炸弹数量用完时不能再放置
+        cells.truncate(capacity.available as usize);
+        if cells.is_empty() {
+            continue;
+        }
+
+        let kind = if pierce.is_some() { BombKind::Pierce } else { BombKind::Normal };
+
+        for cell in cells {
+            capacity.available -= 1;
+            placed.insert(cell);
+
+            // 放置时与炸弹格子重叠的玩家都可以先走开
+            let pass_through = body_query
+                .iter()
+                .filter(|(_, transform)| overlaps_cell(transform.translation, cell))
+                .map(|(entity, _)| entity)
+                .collect();
+
+            // 拥有遥控能力时放置遥控炸弹，记录放置顺序
+            let trigger = if remote.is_some() {
+                *next_order += 1;
+                BombTrigger::Remote { order: *next_order }
+            } else {
+                BombTrigger::Timed
+            };
+
+            let mut world_pos = grid_to_world(cell.x, cell.y);
+            world_pos.z = 5.0; // 设置Z轴，确保炸弹显示在前面
+
+            commands.spawn((
+                TransformBundle::from_transform(Transform::from_translation(world_pos).with_scale(Vec3::splat(3.5))),
+                SpriteFrame::new(Sheet::Bomb, 0),
+                Bomb {
+                    timer: Timer::from_seconds(level.bomb_timer, TimerMode::Once),
+                    range: blast_range.0,
+                    owner: player_entity,
+                    pass_through,
+                    trigger,
+                    kind,
+                },
+                SpriteAnimation::bomb_fuse(),
+                cell,
+            ));
+        }
+
+        // 播放放置炸弹音效
+        sounds.send(SoundEvent::new(Sound::BombPlace, 0.2));
+    }
+}
+
+/// 玩家完全离开炸弹格子后，炸弹对其变为实体
+fn release_bomb_pass_through(
+    mut bomb_query: Query<(&mut Bomb, &GridPosition)>,
+    player_query: Query<&Transform, (With<Player>, Without<Dying>)>,
+) {
+    for (mut bomb, bomb_pos) in bomb_query.iter_mut() {
+        if bomb.pass_through.is_empty() {
+            continue;
+        }
+        bomb.pass_through.retain(|entity| {
+            player_query
+                .get(*entity)
+                .map_or(false, |transform| overlaps_cell(transform.translation, *bomb_pos))
+        });
+    }
+}
+
+/// 拥有踢炸弹能力的玩家走向相邻的炸弹时，把炸弹沿行走方向踢出
+#[allow(clippy::type_complexity)]
+fn kick_bombs(
+    mut commands: Commands,
+    keyboard: Res<Input<KeyCode>>,
+    player_query: Query<(Entity, &Transform, &GridPosition, &PlayerControls), (With<Player>, With<KickAbility>, Without<Stop>, Without<Dying>)>,
+    tile_map: Res<TileMap>,
+    bomb_query: Query<&Bomb, Without<BombSlide>>,
+) {
+    for (player_entity, transform, player_pos, controls) in player_query.iter() {
+        let (horizontal, vertical) = input_axes(&keyboard, controls);
+
+        for direction in [(0, vertical), (horizontal, 0)] {
+            if direction == (0, 0) {
+                continue;
+            }
+            let target = player_pos.step(direction);
+
+            // 只能踢正前方、且已经对该玩家变为实体的炸弹
+            for bomb_entity in tile_map.entities(target, Occupant::Bomb) {
+                let Ok(bomb) = bomb_query.get(bomb_entity) else {
+                    continue;
+                };
+                if !bomb.pass_through.contains(&player_entity)
+                    && pushes_against(transform.translation, direction, target)
+                {
+                    commands.entity(bomb_entity).insert(BombSlide { direction, offset: 0.0 });
+                }
+            }
+        }
+    }
+}
+
+/// 滑动中的炸弹逐格前进，碰到墙体、敌人、玩家或其他炸弹时停在当前格子（计时器照常推进）
+#[allow(clippy::type_complexity)]
+fn slide_bombs(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_paused: Res<GamePaused>,
+    mut bomb_query: Query<(Entity, &mut Transform, &mut GridPosition, Option<&mut BombSlide>), With<Bomb>>,
+    tile_map: Res<TileMap>,
+    body_query: Query<(), (Or<(With<Enemy>, With<Player>)>, Without<Dying>)>,
 ) {
-    if keyboard.just_pressed(KeyCode::Space) {
-        if let Ok(player_pos) = player_query.get_single() {
-            // 检查当前位置是否已有炸弹
-            let has_bomb = bomb_query.iter().any(|bomb_pos| bomb_pos == player_pos);
-            
-            if !has_bomb {
-                let mut world_pos = grid_to_world(player_pos.x, player_pos.y);
-                world_pos.z = 5.0; // 设置Z轴，确保炸弹显示在前面
-                
-                commands.spawn((
-                    SpriteSheetBundle {
-                        texture_atlas: game_textures.bomb.clone(),
-                        sprite: TextureAtlasSprite::new(0), 
-                        transform: Transform::from_translation(world_pos).with_scale(Vec3::splat(3.5)),
-                        ..default()
-                    },
-                    Bomb {
-                        timer: Timer::from_seconds(BOMB_TIMER, TimerMode::Once),
-                        range: EXPLOSION_RANGE,
-                    },
-                    *player_pos,
-                ));
-                
-                // 播放放置炸弹音效（音量60%）
-                audio.play_with_settings(
-                    game_audio.bomb_place.clone(),
-                    PlaybackSettings {
-                        repeat: false,
-                        volume: 0.2,
-                        speed: 1.0,
-                    },
-                );
+    if game_paused.0 {
+        return;
+    }
+
+    let obstructed = |pos: GridPosition| {
+        tile_map.is_solid(pos)
+            || tile_map.occupants(pos).iter().any(|(entity, kind)| {
+                matches!(kind, Occupant::Enemy | Occupant::Player) && body_query.contains(*entity)
+            })
+    };
+    let mut bombs: HashSet<GridPosition> = bomb_query.iter().map(|(_, _, pos, _)| *pos).collect();
+    // 单帧最多前进半格，避免跳过格子检查
+    let step = (BOMB_SLIDE_SPEED * time.delta_seconds()).min(0.5);
+
+    for (entity, mut transform, mut grid_pos, slide) in bomb_query.iter_mut() {
+        let Some(mut slide) = slide else {
+            continue;
+        };
+        let (dx, dy) = slide.direction;
+        let mut offset = slide.offset + step;
+
+        // 越过当前格子中心时检查前方格子是否空闲
+        if slide.offset <= 0.0 && offset > 0.0 {
+            let next = grid_pos.step(slide.direction);
+            if obstructed(next) || bombs.contains(&next) {
+                commands.entity(entity).remove::<BombSlide>();
+                offset = 0.0;
             }
         }
+
+        // 越过格子边界后，炸弹归属到下一个格子
+        if offset >= 0.5 {
+            bombs.remove(&grid_pos);
+            *grid_pos = grid_pos.step(slide.direction);
+            bombs.insert(*grid_pos);
+            offset -= 1.0;
+        }
+        slide.offset = offset;
+
+        let mut world_pos = grid_to_world(grid_pos.x, grid_pos.y);
+        world_pos.x += dx as f32 * offset * CELL_SIZE;
+        world_pos.y -= dy as f32 * offset * CELL_SIZE;
+        world_pos.z = transform.translation.z;
+        transform.translation = world_pos;
     }
 }
 
-/// 炸弹计时器
+/// 遥控引爆：每按一次引爆键，引爆该玩家最早放置的遥控炸弹
+///
+/// 这里只让炸弹的计时器立即到时，实际爆炸仍由 bomb_timer 统一结算。
+#[allow(clippy::type_complexity)]
+fn remote_detonate(
+    keyboard: Res<Input<KeyCode>>,
+    player_query: Query<(Entity, &PlayerControls), (With<Player>, With<RemoteDetonator>, Without<Stop>, Without<Dying>)>,
+    mut bomb_query: Query<&mut Bomb>,
+) {
+    for (player_entity, controls) in player_query.iter() {
+        if !PlayerControls::just_pressed(&keyboard, controls.detonate) {
+            continue;
+        }
+
+        let oldest = bomb_query
+            .iter_mut()
+            .filter(|bomb| bomb.owner == player_entity && !bomb.timer.finished())
+            .filter_map(|bomb| match bomb.trigger {
+                BombTrigger::Remote { order } => Some((order, bomb)),
+                BombTrigger::Timed => None,
+            })
+            .min_by_key(|(order, _)| *order);
+
+        if let Some((_, mut bomb)) = oldest {
+            let remaining = bomb.timer.remaining();
+            bomb.timer.tick(remaining);
+        }
+    }
+}
+
+/// 炸弹计时器（到时的炸弹及其引发的连锁爆炸在同一帧内结算）
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn bomb_timer(
     mut commands: Commands,
     time: Res<Time>,
-    game_textures: Res<GameTextures>,
-    game_audio: Res<GameAudio>,
-    audio: Res<Audio>,
+    mut sounds: EventWriter<SoundEvent>,
+    level: ActiveLevel,
     mut bomb_query: Query<(Entity, &mut Bomb, &GridPosition)>,
-    wall_query: Query<(Entity, &GridPosition), (With<BreakableWall>, Without<Bomb>)>,
-    solid_wall_query: Query<&GridPosition, (With<Wall>, Without<Bomb>)>,
-    enemy_query: Query<(Entity, &GridPosition), (With<Enemy>, Without<Bomb>)>,
-    player_query: Query<(Entity, &GridPosition), (With<Player>, Without<Bomb>)>,
+    mut capacity_query: Query<&mut BombCapacity>,
+    tile_map: Res<TileMap>,
+    mut enemy_query: Query<(Entity, &GridPosition, &EnemyKind, &mut HitPoints), (With<Enemy>, Without<Bomb>, Without<Dying>)>,
+    player_query: Query<(Entity, &GridPosition), (With<Player>, Without<Bomb>, Without<Dying>, Without<Invulnerable>)>,
+    mut score: ResMut<Score>,
+    mut rng: ResMut<SimRng>,
 ) {
+    let Some(level) = level.get() else {
+        return;
+    };
+
+    // 推进计时器，收集到时的炸弹
+    let mut triggered = Vec::new();
     for (bomb_entity, mut bomb, bomb_pos) in bomb_query.iter_mut() {
-        bomb.timer.tick(time.delta());
-        
+        // 遥控炸弹不倒计时；放置者死亡后退化为定时炸弹，避免永远留在场上
+        let counts_down = match bomb.trigger {
+            BombTrigger::Timed => true,
+            BombTrigger::Remote { .. } => !capacity_query.contains(bomb.owner),
+        };
+        if counts_down {
+            bomb.timer.tick(time.delta());
+        }
+
         if bomb.timer.finished() {
-            // 炸弹爆炸
-            commands.entity(bomb_entity).despawn();
-            
-            // 播放爆炸音效
-            audio.play_with_settings(
-                game_audio.bomb_explosion.clone(),
-                PlaybackSettings {
-                    repeat: false,
-                    volume: 0.2,
-                    speed: 1.0,
-                },
-            );
-            
-            // 生成爆炸效果
-            create_explosion(&mut commands, &game_textures, &game_audio, &audio, bomb_pos, bomb.range, &wall_query, &solid_wall_query, &enemy_query, &player_query);
+            triggered.push((*bomb_pos, bomb_entity));
+        }
+    }
+
+    if triggered.is_empty() {
+        return;
+    }
+
+    // 按位置排序，保证同一帧多个炸弹的引爆顺序确定
+    triggered.sort_by_key(|(pos, _)| (pos.y, pos.x));
+    let triggered: Vec<Entity> = triggered.into_iter().map(|(_, entity)| entity).collect();
+
+    let bombs: HashMap<Entity, (GridPosition, i32, BombKind)> = bomb_query
+        .iter()
+        .map(|(entity, bomb, pos)| (entity, (*pos, bomb.range, bomb.kind)))
+        .collect();
+
+    let chain = resolve_chain(&triggered, &bombs, &tile_map);
+
+    for bomb_entity in chain.detonated.iter() {
+        // 炸弹爆炸
+        commands.entity(*bomb_entity).despawn();
+
+        // 归还放置者的炸弹数量（放置者可能已死亡）
+        if let Ok((_, bomb, _)) = bomb_query.get(*bomb_entity) {
+            if let Ok(mut capacity) = capacity_query.get_mut(bomb.owner) {
+                capacity.available = (capacity.available + 1).min(capacity.max);
+            }
         }
     }
+
+    // 播放爆炸音效
+    sounds.send(SoundEvent::new(Sound::BombExplosion, 0.2));
+
+    // 生成爆炸效果
+    create_explosion(
+        &mut commands,
+        &mut sounds,
+        &chain,
+        &level.drops,
+        &tile_map,
+        &mut enemy_query,
+        &player_query,
+        &mut score,
+        &mut rng,
+    );
 }
 
-/// 创建爆炸效果（修复穿透不可破坏墙的bug，使用TextureAtlasSprite）
-fn create_explosion(
-    commands: &mut Commands,
-    game_textures: &GameTextures,
-    game_audio: &GameAudio,
-    audio: &Audio,
-    center: &GridPosition,
-    range: i32,
-    wall_query: &Query<(Entity, &GridPosition), (With<BreakableWall>, Without<Bomb>)>,
-    solid_wall_query: &Query<&GridPosition, (With<Wall>, Without<Bomb>)>,
-    enemy_query: &Query<(Entity, &GridPosition), (With<Enemy>, Without<Bomb>)>,
-    player_query: &Query<(Entity, &GridPosition), (With<Player>, Without<Bomb>)>,
-) {
-    let directions = [
-        (0, 0),   // 中心
-        (1, 0),   // 右
-        (-1, 0),  // 左
-        (0, 1),   // 下
-        (0, -1),  // 上
-    ];
-    
-    for (dx, dy) in directions.iter() {
-        for i in 0..=range {
-            let x = center.x + dx * i;
-            let y = center.y + dy * i;
-            let pos = GridPosition::new(x, y);
-            
-            // 检查是否碰到不可破坏墙（应该停止传播）
-            let mut hit_solid_wall = false;
-            for solid_pos in solid_wall_query.iter() {
-                if solid_pos.x == x && solid_pos.y == y {
-                    hit_solid_wall = true;
+/// 一次（可能连锁的）爆炸的结算结果
+#[derive(Debug, Default)]
+pub struct ChainBlast {
+    /// 按引爆顺序排列的炸弹
+    pub detonated: Vec<Entity>,
+    /// 被火焰覆盖的格子及其火焰形状（每个格子只出现一次）
+    pub tiles: Vec<(GridPosition, FirePiece)>,
+    /// 被摧毁的可破坏墙
+    pub walls: Vec<(Entity, GridPosition)>,
+}
+
+/// 计算连锁爆炸：火焰碰到其他炸弹时立即引爆它
+///
+/// 炸弹按队列顺序依次引爆（先到时的炸弹，再按 右、左、下、上 及由近到远的顺序发现的炸弹），
+/// 每个炸弹只引爆一次，每个格子只被火焰结算一次。穿透炸弹的火焰会穿过可破坏墙继续传播。
+pub fn resolve_chain(
+    triggered: &[Entity],
+    bombs: &HashMap<Entity, (GridPosition, i32, BombKind)>,
+    tile_map: &TileMap,
+) -> ChainBlast {
+    let mut result = ChainBlast::default();
+    let mut queue: VecDeque<Entity> = triggered.iter().copied().collect();
+    let mut detonated = HashSet::new();
+    // 已燃烧的格子在 tiles 中的下标，重叠时合并火焰形状
+    let mut burned: HashMap<GridPosition, usize> = HashMap::new();
+
+    while let Some(bomb_entity) = queue.pop_front() {
+        let Some(&(center, range, kind)) = bombs.get(&bomb_entity) else {
+            continue;
+        };
+        if !detonated.insert(bomb_entity) {
+            continue;
+        }
+        result.detonated.push(bomb_entity);
+
+        // 中心格子
+        burn(&mut result, &mut burned, center, FirePiece::Center);
+
+        for ((dx, dy), cells) in blast_arms(center, range) {
+            // 先确定该方向火焰覆盖的格子，最后一格为末端
+            let mut arm = Vec::new();
+            for pos in cells {
+                // 碰到不可破坏墙，停止该方向的爆炸传播
+                let tile = tile_map.tile(pos);
+                if tile == Tile::Wall {
                     break;
                 }
-            }
-            
-            // 如果碰到不可破坏墙，停止该方向的爆炸传播
-            if hit_solid_wall {
-                break;
-            }
-            
-            // 生成爆炸特效
-            spawn_explosion(commands, game_textures, &pos);
-            
-            // 检查是否摧毁可破坏墙
-            let mut wall_destroyed = false;
-            for (wall_entity, wall_pos) in wall_query.iter() {
-                if wall_pos.x == x && wall_pos.y == y {
-                    commands.entity(wall_entity).despawn();
-                    wall_destroyed = true;
+                arm.push(pos);
+
+                // 碰到其他炸弹：加入引爆队列，由该炸弹继续传播
+                if let Some(other) = tile_map.entities(pos, Occupant::Bomb).find(|other| bombs.contains_key(other)) {
+                    if !detonated.contains(&other) {
+                        queue.push_back(other);
+                    }
+                    break;
+                }
+
+                // 碰到可破坏墙，也停止该方向的爆炸传播（穿透炸弹除外）
+                if tile == Tile::BreakableWall && kind != BombKind::Pierce {
                     break;
                 }
             }
-            
-            // 检查是否击中敌人
-            for (enemy_entity, enemy_pos) in enemy_query.iter() {
-                if enemy_pos.x == x && enemy_pos.y == y {
-                    commands.entity(enemy_entity).despawn();
-                    // 播放敌人死亡音效（音量65%）
-                    audio.play_with_settings(
-                        game_audio.enemy_explosion.clone(),
-                        PlaybackSettings {
-                            repeat: false,
-                            volume: 0.2,
-                            speed: 1.0,
-                        },
-                    );
+
+            let middle = if dx != 0 { FirePiece::Horizontal } else { FirePiece::Vertical };
+            for (i, pos) in arm.iter().enumerate() {
+                let piece = if i + 1 == arm.len() { FirePiece::End(dx, dy) } else { middle };
+                if burn(&mut result, &mut burned, *pos, piece) {
+                    if let Some(wall_entity) = tile_map.find(*pos, Occupant::BreakableWall) {
+                        result.walls.push((wall_entity, *pos));
+                    }
                 }
             }
-            
-            // 检查是否击中玩家
-            for (player_entity, player_pos) in player_query.iter() {
-                if player_pos.x == x && player_pos.y == y {
-                    commands.entity(player_entity).despawn();
-                    // 播放玩家死亡音效（音量70%）
-                    audio.play_with_settings(
-                        game_audio.player_explosion.clone(),
-                        PlaybackSettings {
-                            repeat: false,
-                            volume: 0.1,
-                            speed: 1.0,
-                        },
-                    );
+        }
+    }
+
+    result
+}
+
+/// 标记格子被火焰覆盖，返回是否是第一次被覆盖（已覆盖的格子只合并火焰形状）
+fn burn(
+    result: &mut ChainBlast,
+    burned: &mut HashMap<GridPosition, usize>,
+    pos: GridPosition,
+    piece: FirePiece,
+) -> bool {
+    if let Some(&index) = burned.get(&pos) {
+        let tile = &mut result.tiles[index].1;
+        *tile = tile.merge(piece);
+        return false;
+    }
+    burned.insert(pos, result.tiles.len());
+    result.tiles.push((pos, piece));
+    true
+}
+
+/// 创建爆炸效果：摧毁墙体、击中敌人和玩家
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn create_explosion(
+    commands: &mut Commands,
+    sounds: &mut EventWriter<SoundEvent>,
+    chain: &ChainBlast,
+    drops: &DropTable,
+    tile_map: &TileMap,
+    enemy_query: &mut Query<(Entity, &GridPosition, &EnemyKind, &mut HitPoints), (With<Enemy>, Without<Bomb>, Without<Dying>)>,
+    player_query: &Query<(Entity, &GridPosition), (With<Player>, Without<Bomb>, Without<Dying>, Without<Invulnerable>)>,
+    score: &mut Score,
+    rng: &mut SimRng,
+) {
+    // 每个火焰格子在爆炸瞬间击中的实体，之后停留在火焰中不会被重复伤害
+    let mut hits: HashMap<GridPosition, Vec<Entity>> = chain.tiles.iter().map(|(pos, _)| (*pos, Vec::new())).collect();
+
+    // 摧毁可破坏墙
+    for (wall_entity, pos) in chain.walls.iter() {
+        commands.entity(*wall_entity).despawn();
+        score.total += WALL_SCORE;
+        // 按关卡掉落表决定是否露出道具
+        if let Some(power_up) = drops.roll(&mut rng.0) {
+            spawn_power_up(commands, pos, power_up);
+        }
+    }
+
+    // 按火焰格子查找占用表中的敌人和玩家（占用表已包含本帧的移动，仍以实体当前的格子为准）
+    // 每次爆炸扣1点生命值，同一次（连锁）爆炸中消灭的第 N 个敌人得分乘以 N
+    let mut kills = 0;
+    for (pos, _) in chain.tiles.iter() {
+        for &(entity, occupant) in tile_map.occupants(*pos) {
+            match occupant {
+                Occupant::Enemy => {
+                    let Ok((enemy_entity, enemy_pos, kind, mut hit_points)) = enemy_query.get_mut(entity) else {
+                        continue;
+                    };
+                    let Some(hit) = hits.get_mut(enemy_pos) else {
+                        continue;
+                    };
+                    hit.push(enemy_entity);
+                    if damage_enemy(commands, sounds, enemy_entity, kind, &mut hit_points) {
+                        kills += 1;
+                        score.total += kind.stats().score * kills;
+                    }
+                }
+                Occupant::Player => {
+                    let Ok((player_entity, player_pos)) = player_query.get(entity) else {
+                        continue;
+                    };
+                    let Some(hit) = hits.get_mut(player_pos) else {
+                        continue;
+                    };
+                    hit.push(player_entity);
+                    kill_player(commands, sounds, player_entity);
                 }
+                _ => {}
+            }
+        }
+    }
+
+    // 生成爆炸特效
+    for (pos, piece) in chain.tiles.iter() {
+        let hit = hits.remove(pos).unwrap_or_default();
+        spawn_explosion(commands, pos, *piece, hit);
+    }
+}
+
+/// 爆炸命中敌人：扣1点生命值，生命值耗尽时消灭（返回是否消灭）
+fn damage_enemy(
+    commands: &mut Commands,
+    sounds: &mut EventWriter<SoundEvent>,
+    enemy_entity: Entity,
+    kind: &EnemyKind,
+    hit_points: &mut HitPoints,
+) -> bool {
+    hit_points.0 = hit_points.0.saturating_sub(1);
+    if hit_points.0 > 0 {
+        return false;
+    }
+
+    commands.entity(enemy_entity).insert(Dying);
+    info!("{:?} defeated", kind);
+    // 播放敌人死亡音效
+    sounds.send(SoundEvent::new(Sound::EnemyExplosion, 0.2));
+    true
+}
+
+/// 爆炸命中玩家
+fn kill_player(commands: &mut Commands, sounds: &mut EventWriter<SoundEvent>, player_entity: Entity) {
+    commands.entity(player_entity).insert(Dying);
+    // 播放玩家死亡音效
+    sounds.send(SoundEvent::new(Sound::PlayerExplosion, 0.1));
+}
+
+/// 火焰持续期间，走进火焰格子的玩家和敌人同样会被击中（每个火焰格子对同一实体只生效一次）
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn fire_hazard(
+    mut commands: Commands,
+    mut sounds: EventWriter<SoundEvent>,
+    tile_map: Res<TileMap>,
+    mut explosion_query: Query<(&GridPosition, &mut Explosion)>,
+    mut enemy_query: Query<(Entity, &GridPosition, &EnemyKind, &mut HitPoints), (With<Enemy>, Without<Explosion>, Without<Dying>)>,
+    player_query: Query<(Entity, &GridPosition), (With<Player>, Without<Explosion>, Without<Dying>, Without<Invulnerable>)>,
+    mut score: ResMut<Score>,
+) {
+    for (fire_pos, mut explosion) in explosion_query.iter_mut() {
+        for enemy in tile_map.entities(*fire_pos, Occupant::Enemy) {
+            let Ok((enemy_entity, enemy_pos, kind, mut hit_points)) = enemy_query.get_mut(enemy) else {
+                continue;
+            };
+            // 本帧已被其他火焰消灭的敌人不再结算
+            if enemy_pos != fire_pos || hit_points.0 == 0 || explosion.hit.contains(&enemy_entity) {
+                continue;
+            }
+            explosion.hit.push(enemy_entity);
+            if damage_enemy(&mut commands, &mut sounds, enemy_entity, kind, &mut hit_points) {
+                score.total += kind.stats().score;
             }
-            
-            // 如果遇到可破坏墙，也停止该方向的爆炸传播
-            if wall_destroyed {
-                break;
+        }
+
+        for player in tile_map.entities(*fire_pos, Occupant::Player) {
+            let Ok((player_entity, player_pos)) = player_query.get(player) else {
+                continue;
+            };
+            if player_pos != fire_pos || explosion.hit.contains(&player_entity) {
+                continue;
             }
+            explosion.hit.push(player_entity);
+            kill_player(&mut commands, &mut sounds, player_entity);
         }
     }
 }
 
-/// 生成爆炸特效（使用火焰图片TextureAtlasSprite）
-fn spawn_explosion(commands: &mut Commands, game_textures: &GameTextures, pos: &GridPosition) {
+/// 生成爆炸特效（fire.png 图集）
+fn spawn_explosion(
+    commands: &mut Commands,
+    pos: &GridPosition,
+    piece: FirePiece,
+    hit: Vec<Entity>,
+) {
     let mut world_pos = grid_to_world(pos.x, pos.y);
     world_pos.z = 8.0; // 爆炸效果显示在最上层
     
     commands.spawn((
-        SpriteSheetBundle {
-            texture_atlas: game_textures.fire.clone(),
-            sprite: TextureAtlasSprite::new(8), // 使用第8帧（中心火焰）
-            transform: Transform::from_translation(world_pos).with_scale(Vec3::splat(3.5)),
-            ..default()
-        },
+        TransformBundle::from_transform(Transform::from_translation(world_pos).with_scale(Vec3::splat(3.5))),
+        SpriteFrame::new(Sheet::Fire, fire_frame(piece, 0.0)),
         Explosion {
             timer: Timer::from_seconds(EXPLOSION_DURATION, TimerMode::Once),
+            piece,
+            hit,
         },
+        *pos,
     ));
 }
 
+/// 爆炸动画进度（0~1）对应的图集帧
+fn fire_frame(piece: FirePiece, progress: f32) -> usize {
+    let step = ((progress * FIRE_ANIMATION.len() as f32) as usize).min(FIRE_ANIMATION.len() - 1);
+    FIRE_ANIMATION[step].unwrap_or(piece.sprite_index())
+}
+
 /// 爆炸效果计时器
 fn explosion_timer(
     mut commands: Commands,
     time: Res<Time>,
-    mut explosion_query: Query<(Entity, &mut Explosion)>,
+    mut explosion_query: Query<(Entity, &mut Explosion, &mut SpriteFrame)>,
 ) {
-    for (entity, mut explosion) in explosion_query.iter_mut() {
+    for (entity, mut explosion, mut frame) in explosion_query.iter_mut() {
         explosion.timer.tick(time.delta());
-        
+        let index = fire_frame(explosion.piece, explosion.timer.percent());
+        if frame.index != index {
+            frame.index = index;
+        }
+
         if explosion.timer.finished() {
             commands.entity(entity).despawn();
         }
@@ -230,30 +643,65 @@ fn explosion_timer(
 }
 
 /// 检查游戏结束条件（触发1秒延迟）
+#[allow(clippy::too_many_arguments)]
 fn check_game_over(
     mut commands: Commands,
-    player_query: Query<Entity, With<Player>>,
-    enemy_query: Query<Entity, With<Enemy>>,
+    // 死亡中的敌人视为已死亡；玩家以剩余生命判断是否出局
+    lives: Res<Lives>,
+    enemy_query: Query<Entity, (With<Enemy>, Without<Dying>)>,
+    game_mode: Res<GameMode>,
+    mut match_score: ResMut<MatchScore>,
+    level: ActiveLevel,
+    mut score: ResMut<Score>,
+    level_timer: Res<LevelTimer>,
     delay: Option<Res<GameOverDelay>>,
 ) {
     // 如果已经有延迟计时器，不重复创建
     if delay.is_some() {
         return;
     }
+
+    // 还有剩余生命的玩家
+    let remaining: Vec<usize> = (0..game_mode.players()).filter(|id| lives.get(*id) > 0).collect();
+
+    // 对战模式：只剩一名（或没有）玩家有剩余生命 -> 1秒后结算本局
+    if let GameMode::Versus { .. } = *game_mode {
+        if remaining.len() <= 1 {
+            let winner = remaining.first().copied();
+            if let Some(winner) = winner {
+                match_score.wins[winner] += 1;
+            }
+            match_score.round_winner = winner;
+            commands.insert_resource(GameOverDelay {
+                timer: Timer::from_seconds(1.0, TimerMode::Once),
+                next_state: GameState::RoundOver,
+            });
+        }
+        return;
+    }
     
-    // 玩家死亡 -> 1秒后游戏结束
-    if player_query.is_empty() {
+    // 生命耗尽 -> 1秒后游戏结束
+    if remaining.is_empty() {
         commands.insert_resource(GameOverDelay {
             timer: Timer::from_seconds(1.0, TimerMode::Once),
             next_state: GameState::GameOver,
         });
     }
     
-    // 所有敌人被消灭 -> 1秒后胜利
+    // 所有敌人被消灭 -> 1秒后进入下一关，最后一关则战役通关，越快过关时间奖励越多
     if enemy_query.is_empty() {
+        let seconds_left = (TIME_BONUS_PAR - level_timer.elapsed).max(0.0) as u32;
+        score.time_bonus = seconds_left * TIME_BONUS_PER_SECOND;
+        score.total += score.time_bonus;
+
+        let next_state = if level.is_last() {
+            GameState::CampaignComplete
+        } else {
+            GameState::Victory
+        };
         commands.insert_resource(GameOverDelay {
             timer: Timer::from_seconds(1.0, TimerMode::Once),
-            next_state: GameState::Victory,
+            next_state,
         });
     }
 }
@@ -275,3 +723,112 @@ fn game_over_delay_timer(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 9x9 的空地图和炸弹表
+    struct Arena {
+        tile_map: TileMap,
+        bombs: HashMap<Entity, (GridPosition, i32, BombKind)>,
+        next: u32,
+    }
+
+    impl Arena {
+        fn new() -> Self {
+            Self { tile_map: TileMap::new(9, 9), bombs: HashMap::new(), next: 0 }
+        }
+
+        fn spawn(&mut self, pos: GridPosition, kind: Occupant) -> Entity {
+            let entity = Entity::from_raw(self.next);
+            self.next += 1;
+            self.tile_map.insert(entity, pos, kind);
+            entity
+        }
+
+        fn bomb(&mut self, x: i32, y: i32, range: i32, kind: BombKind) -> Entity {
+            let pos = GridPosition::new(x, y);
+            let entity = self.spawn(pos, Occupant::Bomb);
+            self.bombs.insert(entity, (pos, range, kind));
+            entity
+        }
+
+        fn resolve(&self, triggered: &[Entity]) -> ChainBlast {
+            resolve_chain(triggered, &self.bombs, &self.tile_map)
+        }
+    }
+
+    fn burned(chain: &ChainBlast, x: i32, y: i32) -> bool {
+        chain.tiles.iter().any(|(pos, _)| *pos == GridPosition::new(x, y))
+    }
+
+    #[test]
+    fn cross_chain_detonates_every_arm() {
+        let mut arena = Arena::new();
+        let center = arena.bomb(4, 4, 2, BombKind::Normal);
+        let arms = [
+            arena.bomb(4, 2, 1, BombKind::Normal),
+            arena.bomb(4, 6, 1, BombKind::Normal),
+            arena.bomb(6, 4, 1, BombKind::Normal),
+            arena.bomb(2, 4, 1, BombKind::Normal),
+        ];
+
+        let chain = arena.resolve(&[center]);
+
+        assert_eq!(chain.detonated[0], center);
+        assert_eq!(chain.detonated.len(), 5);
+        assert!(arms.iter().all(|arm| chain.detonated.contains(arm)));
+        // 外侧炸弹继续传播一格
+        assert!(burned(&chain, 4, 1) && burned(&chain, 4, 7) && burned(&chain, 7, 4) && burned(&chain, 1, 4));
+        // 每个格子只出现一次
+        let unique: HashSet<GridPosition> = chain.tiles.iter().map(|(pos, _)| *pos).collect();
+        assert_eq!(unique.len(), chain.tiles.len());
+    }
+
+    #[test]
+    fn looping_chain_detonates_each_bomb_once() {
+        let mut arena = Arena::new();
+        let a = arena.bomb(2, 4, 2, BombKind::Normal);
+        let b = arena.bomb(4, 4, 2, BombKind::Normal);
+
+        assert_eq!(arena.resolve(&[a]).detonated, vec![a, b]);
+        // 同一帧两个都到时也只各引爆一次
+        assert_eq!(arena.resolve(&[a, b]).detonated, vec![a, b]);
+    }
+
+    #[test]
+    fn pierce_passes_breakable_walls() {
+        let mut arena = Arena::new();
+        let near = arena.spawn(GridPosition::new(2, 3), Occupant::BreakableWall);
+        let far = arena.spawn(GridPosition::new(3, 3), Occupant::BreakableWall);
+        let pierce = arena.bomb(1, 3, 3, BombKind::Pierce);
+
+        let chain = arena.resolve(&[pierce]);
+        let walls: Vec<Entity> = chain.walls.iter().map(|(entity, _)| *entity).collect();
+        assert_eq!(walls, vec![near, far]);
+        assert!(burned(&chain, 4, 3));
+
+        // 普通炸弹停在第一面可破坏墙
+        arena.bombs.clear();
+        let normal = arena.bomb(1, 5, 3, BombKind::Normal);
+        arena.spawn(GridPosition::new(2, 5), Occupant::BreakableWall);
+        arena.spawn(GridPosition::new(3, 5), Occupant::BreakableWall);
+        let chain = arena.resolve(&[normal]);
+        assert_eq!(chain.walls.len(), 1);
+        assert!(burned(&chain, 2, 5) && !burned(&chain, 3, 5));
+    }
+
+    #[test]
+    fn solid_wall_stops_blast() {
+        let mut arena = Arena::new();
+        arena.spawn(GridPosition::new(5, 3), Occupant::Wall);
+        let bomb = arena.bomb(3, 3, 3, BombKind::Pierce);
+        let behind = arena.bomb(6, 3, 1, BombKind::Normal);
+
+        let chain = arena.resolve(&[bomb]);
+        assert!(burned(&chain, 4, 3));
+        assert!(!burned(&chain, 5, 3) && !burned(&chain, 6, 3));
+        assert!(!chain.detonated.contains(&behind));
+    }
+}
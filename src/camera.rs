@@ -0,0 +1,96 @@
+use bevy::{ecs::schedule::apply_system_buffers, prelude::*, transform::TransformSystem};
+use crate::{
+    components::*,
+    constants::*,
+    level::ActiveLevel,
+    player::spawn_player,
+    resource::GameState,
+};
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system(follow_players.in_set(OnUpdate(GameState::InGame)))
+            // 进入关卡时立即对准刚生成的玩家（先应用生成命令），避免第一帧停在上一关的位置
+            .add_systems(
+                (apply_system_buffers, follow_players)
+                    .chain()
+                    .after(spawn_player)
+                    .in_schedule(OnEnter(GameState::InGame)),
+            )
+            .add_system(reset_camera.in_schedule(OnExit(GameState::InGame)))
+            .add_system(
+                anchor_screen_space
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// 摄像机跟随存活的玩家（对战模式取所有玩家的中点）
+///
+/// 地图比视口小的方向上地图居中；比视口大时跟随玩家滚动，但不会露出地图外的区域。
+#[allow(clippy::type_complexity)]
+fn follow_players(
+    level: ActiveLevel,
+    player_query: Query<&Transform, (With<Player>, Without<Dying>, Without<MainCamera>)>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    let Some(level) = level.get() else {
+        return;
+    };
+    let Ok(mut camera) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let count = player_query.iter().len();
+    // 玩家全部阵亡（等待复活或结算）时保持原位
+    if count == 0 {
+        return;
+    }
+    let target = player_query.iter().map(|transform| transform.translation.truncate()).sum::<Vec2>() / count as f32;
+
+    let x = scroll_axis(target.x, level.width, WINDOW_WIDTH);
+    // 世界坐标 y 向上，格子 y 向下：按格子方向计算后取反
+    let y = -scroll_axis(-target.y, level.height, WINDOW_HEIGHT - HUD_HEIGHT);
+    // 状态栏占据屏幕顶部，可见网格的中心比屏幕中心低半个状态栏
+    camera.translation.x = x;
+    camera.translation.y = y + HUD_HEIGHT / 2.0;
+}
+
+/// 计算一个轴上视口中心的位置（以格子 0 的中心为原点，沿格子坐标增大的方向为正）
+fn scroll_axis(target: f32, cells: usize, view: f32) -> f32 {
+    let map = cells as f32 * CELL_SIZE;
+    // 地图中心（格子中心之间的距离）
+    let center = (map - CELL_SIZE) / 2.0;
+    if map <= view {
+        return center;
+    }
+    let min = (view - CELL_SIZE) / 2.0;
+    let max = map - (view + CELL_SIZE) / 2.0;
+    target.clamp(min, max)
+}
+
+/// 离开关卡时摄像机回到原点，菜单和结算界面以原点为中心绘制
+fn reset_camera(mut camera_query: Query<&mut Transform, With<MainCamera>>) {
+    for mut camera in camera_query.iter_mut() {
+        camera.translation.x = 0.0;
+        camera.translation.y = 0.0;
+    }
+}
+
+/// 把固定在屏幕上的实体放到摄像机当前位置
+fn anchor_screen_space(
+    camera_query: Query<&Transform, With<MainCamera>>,
+    mut query: Query<(&mut Transform, &ScreenSpace), Without<MainCamera>>,
+) {
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    for (mut transform, screen) in query.iter_mut() {
+        transform.translation.x = camera.translation.x + screen.0.x;
+        transform.translation.y = camera.translation.y + screen.0.y;
+    }
+}
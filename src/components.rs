@@ -4,6 +4,48 @@ use bevy::prelude::*;
 #[derive(Component)]
 pub struct Player;
 
+/// 玩家编号（0~3，对应玩家1~4）
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerId(pub usize);
+
+/// 玩家面朝的方向（y 向下为正），决定一排炸弹的放置方向
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Facing {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Default for Facing {
+    /// 默认朝下
+    fn default() -> Self {
+        Self { x: 0, y: 1 }
+    }
+}
+
+/// 玩家按键绑定
+#[derive(Component, Clone, Copy)]
+pub struct PlayerControls {
+    pub up: &'static [KeyCode],
+    pub down: &'static [KeyCode],
+    pub left: &'static [KeyCode],
+    pub right: &'static [KeyCode],
+    pub bomb: &'static [KeyCode],
+    /// 遥控引爆键
+    pub detonate: &'static [KeyCode],
+}
+
+impl PlayerControls {
+    /// 是否按住了任一按键
+    pub fn pressed(keyboard: &Input<KeyCode>, keys: &[KeyCode]) -> bool {
+        keyboard.any_pressed(keys.iter().copied())
+    }
+
+    /// 是否刚刚按下任一按键
+    pub fn just_pressed(keyboard: &Input<KeyCode>, keys: &[KeyCode]) -> bool {
+        keyboard.any_just_pressed(keys.iter().copied())
+    }
+}
+
 /// 敌人组件
 #[derive(Component)]
 pub struct Enemy;
@@ -16,15 +58,111 @@ pub struct EnemyDirection {
 }
 
 impl EnemyDirection {
-    pub fn random() -> Self {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)]; // 上下右左
+    pub fn random(rng: &mut impl rand::Rng) -> Self {
+        let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)]; // 下上右左
         let (x, y) = directions[rng.gen_range(0..4)];
         Self { x, y }
     }
 }
 
+/// 敌人类型
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EnemyKind {
+    /// 慢速游走者
+    #[default]
+    Wanderer,
+    /// 快速追踪者
+    Chaser,
+    /// 可穿过可破坏墙的幽灵
+    Ghost,
+    /// 需要多次命中的坦克
+    Tank,
+}
+
+/// 敌人类型的属性
+pub struct EnemyStats {
+    /// 精灵缩放
+    pub scale: f32,
+    /// 移动间隔倍率（相对关卡难度，越小越快）
+    pub move_interval_scale: f32,
+    pub hit_points: u32,
+    /// 消灭后获得的分数
+    pub score: u32,
+    /// 默认AI策略
+    pub behaviour: EnemyBehaviour,
+    /// 是否能穿过可破坏墙
+    pub passes_walls: bool,
+}
+
+impl EnemyKind {
+    pub fn stats(self) -> EnemyStats {
+        match self {
+            Self::Wanderer => EnemyStats {
+                scale: 3.5,
+                move_interval_scale: 1.2,
+                hit_points: 1,
+                score: 100,
+                behaviour: EnemyBehaviour::Wander,
+                passes_walls: false,
+            },
+            Self::Chaser => EnemyStats {
+                scale: 3.5,
+                move_interval_scale: 0.7,
+                hit_points: 1,
+                score: 200,
+                behaviour: EnemyBehaviour::Hunter,
+                passes_walls: false,
+            },
+            Self::Ghost => EnemyStats {
+                scale: 3.5,
+                move_interval_scale: 1.0,
+                hit_points: 1,
+                score: 300,
+                behaviour: EnemyBehaviour::Chase,
+                passes_walls: true,
+            },
+            Self::Tank => EnemyStats {
+                scale: 4.2,
+                move_interval_scale: 1.5,
+                hit_points: 3,
+                score: 500,
+                behaviour: EnemyBehaviour::Evade,
+                passes_walls: false,
+            },
+        }
+    }
+}
+
+/// 生命值（被爆炸命中一次减1）
+#[derive(Component)]
+pub struct HitPoints(pub u32);
+
+/// 敌人AI策略（每个敌人独立选择）
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EnemyBehaviour {
+    /// 游走：沿直线前进，受阻时随机换向
+    #[default]
+    Wander,
+    /// 追踪：BFS 寻路接近玩家，无路可走时游走
+    Chase,
+    /// 谨慎：躲开即将爆炸的格子，其余时间游走
+    Evade,
+    /// 猎手：躲开即将爆炸的格子，同时追踪玩家
+    Hunter,
+}
+
+impl EnemyBehaviour {
+    /// 是否追踪玩家
+    pub fn chases(self) -> bool {
+        matches!(self, Self::Chase | Self::Hunter)
+    }
+
+    /// 是否躲避炸弹
+    pub fn avoids_blasts(self) -> bool {
+        matches!(self, Self::Evade | Self::Hunter)
+    }
+}
+
 /// 敌人移动计时器
 #[derive(Component)]
 pub struct EnemyMoveTimer {
@@ -32,7 +170,7 @@ pub struct EnemyMoveTimer {
 }
 
 /// 网格位置组件
-#[derive(Component, Clone, Copy, Debug, PartialEq)]
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct GridPosition {
     pub x: i32,
     pub y: i32,
@@ -49,12 +187,103 @@ impl GridPosition {
 pub struct Bomb {
     pub timer: Timer,
     pub range: i32,
+    /// 放置炸弹的玩家
+    pub owner: Entity,
+    /// 放置时站在炸弹上的实体，离开之前可以穿过炸弹
+    pub pass_through: Vec<Entity>,
+    /// 引爆方式
+    pub trigger: BombTrigger,
+    pub kind: BombKind,
+}
+
+/// 炸弹类型（决定火焰如何传播）
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BombKind {
+    /// 火焰在第一面可破坏墙处停止
+    #[default]
+    Normal,
+    /// 火焰穿透可破坏墙，直到达到爆炸范围或碰到不可破坏墙
+    Pierce,
+}
+
+/// 炸弹的引爆方式
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BombTrigger {
+    /// 计时结束后爆炸
+    Timed,
+    /// 不倒计时，由放置者按引爆键按放置顺序引爆
+    Remote {
+        /// 放置序号（越小越先引爆）
+        order: u64,
+    },
+}
+
+/// 被踢出的炸弹正在滑动
+#[derive(Component)]
+pub struct BombSlide {
+    /// 滑动方向（y 向下为正）
+    pub direction: (i32, i32),
+    /// 相对当前 GridPosition 中心的偏移（格子，-0.5..0.5）
+    pub offset: f32,
 }
 
 /// 爆炸效果组件
 #[derive(Component)]
 pub struct Explosion {
     pub timer: Timer,
+    pub piece: FirePiece,
+    /// 已被该火焰格子击中的实体（火焰持续期间每个实体只结算一次）
+    pub hit: Vec<Entity>,
+}
+
+/// 爆炸十字中每个格子的火焰形状
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FirePiece {
+    /// 十字中心
+    Center,
+    /// 水平方向的中段
+    Horizontal,
+    /// 垂直方向的中段
+    Vertical,
+    /// 火焰末端（方向为火焰传播方向，y 向下为正）
+    End(i32, i32),
+}
+
+impl FirePiece {
+    /// fire.png（4x3）图集中对应的帧
+    pub fn sprite_index(self) -> usize {
+        match self {
+            Self::Center => 2,
+            Self::Horizontal => 1,
+            Self::Vertical => 5,
+            Self::End(-1, _) => 0,
+            Self::End(1, _) => 3,
+            Self::End(_, -1) => 4,
+            Self::End(..) => 6,
+        }
+    }
+
+    /// 多个炸弹的火焰覆盖同一格子时合并形状：同轴合并为中段，不同轴合并为中心
+    pub fn merge(self, other: Self) -> Self {
+        if self == other {
+            return self;
+        }
+        match (self.axis(), other.axis()) {
+            (Some(true), Some(true)) => Self::Horizontal,
+            (Some(false), Some(false)) => Self::Vertical,
+            _ => Self::Center,
+        }
+    }
+
+    /// 火焰所在的轴（Some(true) 为水平，Some(false) 为垂直，中心为 None）
+    fn axis(self) -> Option<bool> {
+        match self {
+            Self::Center => None,
+            Self::Horizontal => Some(true),
+            Self::Vertical => Some(false),
+            Self::End(dx, _) => Some(dx != 0),
+        }
+    }
 }
 
 /// 墙体组件（不可破坏）
@@ -65,6 +294,60 @@ pub struct Wall;
 #[derive(Component)]
 pub struct BreakableWall;
 
+/// 道具组件（藏在可破坏墙下，被炸开后出现）
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerUp {
+    /// 炸弹数量+1
+    BombUp,
+    /// 爆炸范围+1
+    FireUp,
+    /// 移动速度提升
+    SpeedUp,
+    /// 踢炸弹
+    Kick,
+    /// 遥控炸弹
+    Remote,
+    /// 穿透炸弹
+    Pierce,
+    /// 一排炸弹
+    LineBomb,
+}
+
+/// 玩家可同时放置的炸弹数量
+#[derive(Component)]
+pub struct BombCapacity {
+    /// 炸弹上限
+    pub max: u32,
+    /// 当前可放置的炸弹数（炸弹爆炸后归还）
+    pub available: u32,
+}
+
+impl BombCapacity {
+    pub fn new(max: u32) -> Self {
+        Self { max, available: max }
+    }
+}
+
+/// 玩家炸弹的爆炸范围（格子数）
+#[derive(Component)]
+pub struct BlastRange(pub i32);
+
+/// 踢炸弹能力标记
+#[derive(Component)]
+pub struct KickAbility;
+
+/// 遥控引爆能力标记
+#[derive(Component)]
+pub struct RemoteDetonator;
+
+/// 穿透炸弹能力标记
+#[derive(Component)]
+pub struct PierceBombs;
+
+/// 一排炸弹能力标记：一次沿面朝方向放下所有剩余炸弹
+#[derive(Component)]
+pub struct LineBomb;
+
 /// 移动速度组件
 #[derive(Component)]
 pub struct Speed(pub f32);
@@ -85,10 +368,80 @@ pub struct GameOverUI;
 #[derive(Component)]
 pub struct VictoryUI;
 
+/// 战役通关界面UI标记
+#[derive(Component)]
+pub struct CampaignCompleteUI;
+
+/// 对战局结束界面UI标记
+#[derive(Component)]
+pub struct RoundOverUI;
+
+/// 游戏内HUD标记
+#[derive(Component)]
+pub struct HudUI;
+
+/// 顶部状态栏文字
+#[derive(Component)]
+pub struct HudText;
+
+/// 主摄像机标记
+#[derive(Component)]
+pub struct MainCamera;
+
+/// 固定在屏幕上的实体（状态栏、暂停遮罩、背景等），每帧跟随摄像机放置
+///
+/// 值为相对屏幕中心的偏移（像素，y 向上为正）。
+#[derive(Component, Clone, Copy, Default)]
+pub struct ScreenSpace(pub Vec2);
+
 /// 背景暗化遮罩
 #[derive(Component)]
 pub struct DimOverlay;
 
+/// 复活后的无敌状态（期间闪烁，不会被爆炸和敌人杀死）
+#[derive(Component)]
+pub struct Invulnerable {
+    pub timer: Timer,
+    /// 闪烁间隔（秒）
+    pub blink_interval: f32,
+}
+
+impl Invulnerable {
+    pub fn new(duration: f32, blink_interval: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+            blink_interval,
+        }
+    }
+}
+
+/// 精灵图集
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sheet {
+    Player,
+    Enemy,
+    Wall,
+    Bomb,
+    Fire,
+}
+
+/// 实体显示的图集帧：玩法逻辑只修改帧号，真正的精灵由 SpritesPlugin（graphics 特性）挂上并同步
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpriteFrame {
+    pub sheet: Sheet,
+    pub index: usize,
+}
+
+impl SpriteFrame {
+    pub fn new(sheet: Sheet, index: usize) -> Self {
+        Self { sheet, index }
+    }
+}
+
+/// 死亡中标记：播放死亡动画，不再响应输入和碰撞，动画结束后移除实体
+#[derive(Component)]
+pub struct Dying;
+
 /// 暂停标记组件（用于暂停实体的行为）
 #[derive(Component)]
 pub struct Stop;
@@ -1,35 +1,94 @@
-/// 窗口宽度
-pub const WINDOW_WIDTH: f32 = 780.0;
-/// 窗口高度
-pub const WINDOW_HEIGHT: f32 = 780.0;
 
-/// 网格大小（多少行/列）
-pub const GRID_SIZE: usize = 13;
+/// 视口可见的列数（地图尺寸由关卡文件决定，更大的地图由摄像机跟随玩家滚动）
+pub const VIEW_COLUMNS: usize = 13;
+/// 视口可见的行数
+pub const VIEW_ROWS: usize = 13;
 /// 每个格子的像素大小
 pub const CELL_SIZE: f32 = 60.0;
 
+/// 顶部状态栏高度（位于网格上方）
+pub const HUD_HEIGHT: f32 = 80.0;
+/// 窗口宽度
+pub const WINDOW_WIDTH: f32 = VIEW_COLUMNS as f32 * CELL_SIZE;
+/// 窗口高度（可见网格 + 顶部状态栏）
+pub const WINDOW_HEIGHT: f32 = VIEW_ROWS as f32 * CELL_SIZE + HUD_HEIGHT;
+
 /// 玩家大小
 pub const PLAYER_SIZE: f32 = 50.0;
-/// 墙体大小
-pub const WALL_SIZE: f32 = 60.0;
-/// 炸弹大小
-pub const BOMB_SIZE: f32 = 50.0;
-/// 敌人大小
-pub const ENEMY_SIZE: f32 = 50.0;
-/// 火焰大小
-pub const FIRE_SIZE: f32 = 55.0;
 
 /// 炸弹爆炸时间（秒）
 pub const BOMB_TIMER: f32 = 3.0;
 /// 爆炸持续时间（秒）
 pub const EXPLOSION_DURATION: f32 = 0.5;
+
+/// 精灵动画每帧时长（秒）
+pub const WALK_FRAME_TIME: f32 = 0.12;
+pub const DEATH_FRAME_TIME: f32 = 0.1;
+pub const FUSE_FRAME_TIME: f32 = 0.2;
+/// 爆炸动画：小火苗 -> 中火苗 -> 火焰形状（保持） -> 中火苗 -> 小火苗，None 表示当前格子的火焰形状
+pub const FIRE_ANIMATION: [Option<usize>; 7] = [Some(8), Some(9), None, None, None, Some(9), Some(8)];
 /// 爆炸范围（格子数）
 pub const EXPLOSION_RANGE: i32 = 2;
 
+/// 最多玩家数量
+pub const MAX_PLAYERS: usize = 4;
+/// 对战模式局数（N局多胜制）
+pub const VERSUS_BEST_OF: u32 = 5;
+/// 赢得比赛所需的局数
+pub const VERSUS_WINS_NEEDED: u32 = VERSUS_BEST_OF / 2 + 1;
+/// 得分：炸毁一面可破坏墙
+pub const WALL_SCORE: u32 = 10;
+/// 过关时间奖励：在该时间（秒）内过关，每提前一秒奖励 TIME_BONUS_PER_SECOND 分
+pub const TIME_BONUS_PAR: f32 = 120.0;
+pub const TIME_BONUS_PER_SECOND: u32 = 20;
+/// 关卡默认时间限制（秒）
+pub const DEFAULT_TIME_LIMIT: f32 = 180.0;
+/// 剩余时间低于该值（秒）时进入 HURRY UP 阶段
+pub const HURRY_UP_TIME: f32 = 30.0;
+/// HURRY UP 阶段的音乐播放速度
+pub const HURRY_UP_MUSIC_SPEED: f32 = 1.3;
+/// HURRY UP 阶段敌人移动间隔的倍率（越小越快）
+pub const HURRY_UP_ENEMY_INTERVAL_SCALE: f32 = 0.6;
+/// 时间耗尽后，每隔多少秒落下一块缩圈墙
+pub const CLOSING_WALL_INTERVAL: f32 = 0.15;
+/// 每位玩家的初始生命数
+pub const STARTING_LIVES: u32 = 3;
+/// 复活后的无敌时间（秒）及闪烁间隔
+pub const INVULNERABILITY_DURATION: f32 = 3.0;
+pub const INVULNERABILITY_BLINK_INTERVAL: f32 = 0.1;
+/// 各玩家使用 player.png 图集的第几行（每行14帧，对应不同角色）
+pub const PLAYER_SPRITE_ROWS: [usize; MAX_PLAYERS] = [3, 0, 1, 2];
+
 /// 玩家移动速度
 pub const PLAYER_SPEED: f32 = 3.0;
+/// 转角辅助：偏离通道中心不超过该距离（格子）时自动滑入通道
+pub const CORNER_ASSIST: f32 = 0.45;
+/// 玩家初始炸弹数量
+pub const INITIAL_BOMB_CAPACITY: u32 = 1;
+
+/// 道具上限
+pub const MAX_BOMB_CAPACITY: u32 = 8;
+pub const MAX_BLAST_RANGE: i32 = 8;
+pub const MAX_PLAYER_SPEED: f32 = 6.0;
+/// 速度道具每次提升的速度
+pub const SPEED_UP_AMOUNT: f32 = 0.5;
+/// 被踢出的炸弹滑动速度（格子/秒）
+pub const BOMB_SLIDE_SPEED: f32 = 8.0;
+/// 道具大小
+pub const POWER_UP_SIZE: f32 = 44.0;
+
+/// 可破坏墙被炸毁时掉落道具的默认概率
+pub const DEFAULT_DROP_CHANCE: f32 = 0.3;
+/// 各道具的默认掉落权重（炸弹、火力、速度、踢炸弹、遥控、穿透、一排炸弹）
+pub const DEFAULT_DROP_WEIGHTS: [u32; 7] = [3, 3, 2, 1, 1, 1, 1];
 /// 敌人移动速度
 pub const ENEMY_SPEED: f32 = 100.0;
+/// 第一关敌人移动间隔（秒）
+pub const ENEMY_MOVE_INTERVAL: f32 = 0.5;
+/// 每关敌人移动间隔的缩放系数（越小越快）
+pub const ENEMY_SPEEDUP_PER_LEVEL: f32 = 0.8;
+/// 敌人移动间隔下限（秒）
+pub const ENEMY_MIN_MOVE_INTERVAL: f32 = 0.2;
 
 /// 背景图片
 pub const BACKGROUND_SPRITE: &str = "images/background.png";
@@ -38,11 +97,40 @@ pub const BACKGROUND_SPRITE: &str = "images/background.png";
 pub const PLAYER_SPRITE: &str = "images/player.png";
 pub const ENEMY_SPRITE: &str = "images/creature.png";
 pub const WALL_SPRITE: &str = "images/wall.png"; // 不可破坏墙
-pub const BREAKABLE_WALL_SPRITE: &str = "images/door.png"; // 可破坏墙
 pub const BOMB_SPRITE: &str = "images/bomb.png"; // 炸弹实体
 pub const FIRE_SPRITE: &str = "images/fire.png"; // 爆炸火焰
 pub const FONT_PATH: &str = "fonts/kenney_blocks.ttf";
 
+/// 对战地图文件路径
+pub const VERSUS_LEVEL_PATH: &str = "levels/versus.level";
+
+/// 随机地图尺寸（奇数，保证外墙和柱子的排列完整）
+pub const RANDOM_MAP_WIDTH: usize = 21;
+pub const RANDOM_MAP_HEIGHT: usize = 13;
+/// 随机地图中可破坏墙占空地的比例
+pub const RANDOM_MAP_DENSITY: f32 = 0.5;
+/// 随机地图第一关的敌人数量（之后每关多一个）
+pub const RANDOM_MAP_ENEMIES: usize = 4;
+/// 额外的敌人出生点离玩家出生点的最小曼哈顿距离
+pub const RANDOM_MAP_SAFE_DISTANCE: i32 = 6;
+
+/// `--headless` 默认模拟的局数
+pub const HEADLESS_ROUNDS: usize = 100;
+/// 无窗口模拟的固定时间步长（秒）
+pub const HEADLESS_STEP: f32 = 1.0 / 60.0;
+/// 无窗口模拟每局的最长游戏时间（秒），超过后记为未分出结果
+pub const HEADLESS_MAX_SECONDS: f32 = 600.0;
+/// 随机机器人每一步按下炸弹键（和遥控引爆键）的概率
+pub const HEADLESS_BOT_BOMB_CHANCE: f64 = 0.02;
+
+/// 战役关卡文件路径（按顺序游玩）
+pub const LEVEL_PATHS: [&str; 4] = [
+    "levels/level1.level",
+    "levels/level2.level",
+    "levels/level3.level",
+    "levels/level4.level",
+];
+
 /// 音频路径
 pub const AUDIO_GAME_OVER: &str = "audios/game_over_bad_chest.wav"; // 游戏失败 (WAV格式)
 pub const AUDIO_ENEMY_EXPLOSION: &str = "audios/enemy_explosion.ogg"; // 敌人死亡 (OGG格式)
@@ -50,6 +138,7 @@ pub const AUDIO_PLAYER_EXPLOSION: &str = "audios/player_explosion.ogg"; // 玩
 pub const AUDIO_BOMB_PLACE: &str = "audios/bomb_place.ogg"; // 放置炸弹 (OGG格式)
 pub const AUDIO_VICTORY: &str = "audios/victory.wav"; // 胜利音乐 (WAV格式，3秒)
 pub const AUDIO_BOMB_EXPLOSION: &str = "audios/8bit_bomb_explosion.wav"; // 炸弹爆炸 (WAV格式)
+pub const AUDIO_MUSIC: &str = "audios/snd_music_victorytheme.ogg"; // 关卡背景音乐 (OGG格式)
 
 /// 颜色定义
 pub const COLOR_BACKGROUND: (f32, f32, f32) = (0.9, 0.9, 0.92); // 浅灰色偏白背景
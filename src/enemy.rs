@@ -1,9 +1,14 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashSet};
 use crate::{
+    animation::{ClipName, SpriteAnimation},
     components::*,
     constants::*,
-    map::grid_to_world,
-    resource::{GameState, GameTextures, GameAudio},
+    level::{ActiveLevel, Tile},
+    grid::grid_to_world,
+    pathfinding::{bfs_first_step, danger_tiles},
+    resource::{GameState, SimRng},
+    sound::{Sound, SoundEvent},
+    tilemap::{Occupant, TileMap, TileMapSet},
 };
 
 pub struct EnemyPlugin;
@@ -11,107 +16,175 @@ pub struct EnemyPlugin;
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app
+            .add_event::<SoundEvent>()
             .add_system(spawn_enemies.in_schedule(OnEnter(GameState::InGame)))
-            .add_system(enemy_movement.in_set(OnUpdate(GameState::InGame)))
-            .add_system(check_player_enemy_collision.in_set(OnUpdate(GameState::InGame)));
+            .add_system(enemy_movement.in_set(OnUpdate(GameState::InGame)).in_set(TileMapSet::Move))
+            .add_system(check_player_enemy_collision.in_set(OnUpdate(GameState::InGame)).after(TileMapSet::Sync));
     }
 }
 
-/// 生成敌人（使用TextureAtlasSprite）
-fn spawn_enemies(mut commands: Commands, game_textures: Res<GameTextures>) {
-    // 在地图的几个角落放置敌人
-    let enemy_positions = [
-        (11, 1),
-        (11, 11),
-        (1, 11),
-    ];
-    
-    for (x, y) in enemy_positions.iter() {
-        let grid_pos = GridPosition::new(*x, *y);
+/// 生成敌人（creature.png 图集，所有类型共用四方向行走帧，着色和缩放区分类型）
+fn spawn_enemies(mut commands: Commands, level: ActiveLevel, mut rng: ResMut<SimRng>) {
+    let difficulty = level.difficulty();
+    let Some(level) = level.get() else {
+        return;
+    };
+
+    // 在关卡指定的出生点放置对应类型的敌人
+    for spawn in level.enemy_spawns.iter() {
+        let (grid_pos, kind) = (spawn.pos, spawn.kind);
+        let stats = kind.stats();
         let mut world_pos = grid_to_world(grid_pos.x, grid_pos.y);
         world_pos.z = 10.0; // 敌人在最上层
         
         commands.spawn((
-            SpriteSheetBundle {
-                texture_atlas: game_textures.enemy.clone(),
-                sprite: TextureAtlasSprite::new(0), // 使用第0帧
-                transform: Transform::from_translation(world_pos).with_scale(Vec3::splat(3.5)),
-                ..default()
-            },
+            TransformBundle::from_transform(Transform::from_translation(world_pos).with_scale(Vec3::splat(stats.scale))),
+            SpriteFrame::new(Sheet::Enemy, 1),
             Enemy,
+            SpriteAnimation::character(0, 1),
+            kind,
             grid_pos,
             Speed(ENEMY_SPEED),
-            EnemyDirection::random(), // 随机初始方向
+            HitPoints(stats.hit_points),
+            EnemyDirection::random(&mut rng.0), // 随机初始方向
+            spawn.behaviour.unwrap_or(stats.behaviour),
             EnemyMoveTimer {
-                timer: Timer::from_seconds(0.5, TimerMode::Repeating), // 每0.5秒尝试移动一次
+                // 移动间隔随关卡递减，并按敌人类型缩放
+                timer: Timer::from_seconds(
+                    difficulty.enemy_move_interval * stats.move_interval_scale,
+                    TimerMode::Repeating,
+                ),
             },
         ));
     }
 }
 
-/// 敌人移动系统（离散移动，每0.5秒跳一格）
+/// 敌人移动系统（离散移动，每个移动间隔跳一格，下一步由AI策略决定）
+#[allow(clippy::type_complexity)]
 fn enemy_movement(
     time: Res<Time>,
     mut enemy_query: Query<
-        (&mut Transform, &mut GridPosition, &mut EnemyDirection, &mut EnemyMoveTimer),
-        (With<Enemy>, Without<Stop>)
+        (
+            Entity,
+            &mut Transform,
+            &mut GridPosition,
+            &mut EnemyDirection,
+            &mut EnemyMoveTimer,
+            &mut SpriteAnimation,
+            &EnemyBehaviour,
+            &EnemyKind,
+        ),
+        (With<Enemy>, Without<Stop>, Without<Dying>)
     >,
-    wall_query: Query<&GridPosition, (Or<(With<Wall>, With<BreakableWall>)>, Without<Enemy>)>,
-    bomb_query: Query<&GridPosition, (With<Bomb>, Without<Enemy>)>,
+    player_query: Query<&GridPosition, (With<Player>, Without<Enemy>, Without<Dying>)>,
+    tile_map: Res<TileMap>,
+    bomb_query: Query<(&GridPosition, &Bomb), Without<Enemy>>,
+    mut rng: ResMut<SimRng>,
 ) {
-    for (mut transform, mut grid_pos, mut direction, mut move_timer) in enemy_query.iter_mut() {
-        // 更新移动计时器
+    // 更新移动计时器，收集本帧需要移动的敌人
+    let mut ready = Vec::new();
+    for (entity, _, _, _, mut move_timer, _, _, _) in enemy_query.iter_mut() {
         move_timer.timer.tick(time.delta());
-        
         if move_timer.timer.just_finished() {
-            // 计算新位置
-            let new_x = grid_pos.x + direction.x;
-            let new_y = grid_pos.y + direction.y;
-            let new_grid = GridPosition::new(new_x, new_y);
-            
-            // 检查新位置是否被阻挡
-            let blocked = wall_query.iter().any(|pos| *pos == new_grid)
-                || bomb_query.iter().any(|pos| *pos == new_grid);
-            
-            if !blocked {
-                // 可以移动
-                *grid_pos = new_grid;
-                let mut new_world_pos = grid_to_world(new_grid.x, new_grid.y);
-                new_world_pos.z = 10.0;
-                transform.translation = new_world_pos;
-            } else {
-                // 被阻挡，换个随机方向
-                *direction = EnemyDirection::random();
-            }
+            ready.push(entity);
+        }
+    }
+
+    if ready.is_empty() {
+        return;
+    }
+
+    let players: Vec<GridPosition> = player_query.iter().copied().collect();
+    let danger = danger_tiles(
+        bomb_query.iter().map(|(pos, bomb)| (*pos, bomb.range, bomb.kind)),
+        |pos| tile_map.tile(*pos) == Tile::Wall,
+        |pos| tile_map.tile(*pos) == Tile::BreakableWall,
+    );
+
+    for entity in ready {
+        let Ok((_, mut transform, mut grid_pos, mut direction, _, mut animation, behaviour, kind)) = enemy_query.get_mut(entity) else {
+            continue;
+        };
+
+        // 墙体和炸弹都会阻挡敌人（幽灵可以穿过可破坏墙）
+        let passes_walls = kind.stats().passes_walls;
+        let blocked = |pos: &GridPosition| {
+            let wall = match tile_map.tile(*pos) {
+                Tile::Wall => true,
+                Tile::BreakableWall => !passes_walls,
+                Tile::Empty => false,
+            };
+            wall || tile_map.contains(*pos, Occupant::Bomb)
+        };
+
+        if let Some(new_grid) = choose_step(*behaviour, *grid_pos, &mut direction, &players, &blocked, &danger, &mut rng.0) {
+            direction.x = new_grid.x - grid_pos.x;
+            direction.y = new_grid.y - grid_pos.y;
+            animation.play(ClipName::walk(direction.x, direction.y));
+            *grid_pos = new_grid;
+            let mut new_world_pos = grid_to_world(new_grid.x, new_grid.y);
+            new_world_pos.z = 10.0;
+            transform.translation = new_world_pos;
         }
     }
 }
 
+/// 根据AI策略选择下一步要走的格子（None 表示本次不移动）
+fn choose_step(
+    behaviour: EnemyBehaviour,
+    pos: GridPosition,
+    direction: &mut EnemyDirection,
+    players: &[GridPosition],
+    blocked: &impl Fn(&GridPosition) -> bool,
+    danger: &HashSet<GridPosition>,
+    rng: &mut impl rand::Rng,
+) -> Option<GridPosition> {
+    let avoids_blasts = behaviour.avoids_blasts();
+    let is_safe = |pos: &GridPosition| !avoids_blasts || !danger.contains(pos);
+
+    // 躲避：站在爆炸范围内时，寻路到最近的安全格子
+    if avoids_blasts && danger.contains(&pos) {
+        if let Some(step) = bfs_first_step(pos, |p| !danger.contains(p), |p| !blocked(p)) {
+            return Some(step);
+        }
+    }
+
+    // 追踪：寻路接近玩家，不主动走进爆炸范围
+    if behaviour.chases() {
+        if let Some(step) = bfs_first_step(pos, |p| players.contains(p), |p| !blocked(p) && is_safe(p)) {
+            return Some(step);
+        }
+    }
+
+    // 游走：沿当前方向前进，被阻挡时换个随机方向
+    let forward = pos.step((direction.x, direction.y));
+    if !blocked(&forward) && is_safe(&forward) {
+        Some(forward)
+    } else {
+        *direction = EnemyDirection::random(rng);
+        None
+    }
+}
+
 /// 检查玩家与敌人的碰撞
+#[allow(clippy::type_complexity)]
 fn check_player_enemy_collision(
     mut commands: Commands,
-    game_audio: Res<GameAudio>,
-    audio: Res<Audio>,
-    player_query: Query<(Entity, &GridPosition), With<Player>>,
-    enemy_query: Query<&GridPosition, With<Enemy>>,
+    mut sounds: EventWriter<SoundEvent>,
+    player_query: Query<(Entity, &GridPosition), (With<Player>, Without<Dying>, Without<Invulnerable>)>,
+    tile_map: Res<TileMap>,
+    enemy_query: Query<&GridPosition, (With<Enemy>, Without<Dying>)>,
 ) {
-    if let Ok((player_entity, player_pos)) = player_query.get_single() {
+    for (player_entity, player_pos) in player_query.iter() {
         // 检查玩家是否与任何敌人在同一格
-        for enemy_pos in enemy_query.iter() {
-            if player_pos == enemy_pos {
-                // 玩家被敌人杀死
-                commands.entity(player_entity).despawn();
-                // 播放玩家死亡音效
-                audio.play_with_settings(
-                    game_audio.player_explosion.clone(),
-                    PlaybackSettings {
-                        repeat: false,
-                        volume: 0.2,
-                        speed: 1.0,
-                    },
-                );
-                return;
-            }
+        let touching = tile_map
+            .entities(*player_pos, Occupant::Enemy)
+            .any(|enemy| enemy_query.get(enemy) == Ok(player_pos));
+        if touching {
+            // 玩家被敌人杀死
+            commands.entity(player_entity).insert(Dying);
+            // 播放玩家死亡音效
+            sounds.send(SoundEvent::new(Sound::PlayerExplosion, 0.2));
         }
     }
 }
@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use crate::{components::GridPosition, constants::CELL_SIZE};
+
+/// 上下左右四个方向（网格坐标系，y 向下为正）
+pub const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (0, 1), (1, 0), (-1, 0)];
+
+/// 爆炸十字四条臂的顺序：右、左、下、上（决定连锁爆炸中发现其他炸弹的顺序）
+pub const BLAST_DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+impl GridPosition {
+    /// 沿方向移动若干格
+    pub fn offset(self, (dx, dy): (i32, i32), distance: i32) -> Self {
+        Self::new(self.x + dx * distance, self.y + dy * distance)
+    }
+
+    /// 沿方向相邻的一格
+    pub fn step(self, direction: (i32, i32)) -> Self {
+        self.offset(direction, 1)
+    }
+
+    /// 上下左右四个相邻格子（按 DIRECTIONS 的顺序）
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        DIRECTIONS.into_iter().map(move |direction| self.step(direction))
+    }
+
+    /// 曼哈顿距离
+    pub fn manhattan(self, other: Self) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+}
+
+/// 格子是否在 width x height 的地图内
+pub fn in_bounds(pos: GridPosition, width: usize, height: usize) -> bool {
+    pos.x >= 0 && pos.y >= 0 && (pos.x as usize) < width && (pos.y as usize) < height
+}
+
+/// 按行（从上到下、从左到右）遍历地图的所有格子
+pub fn cells(width: usize, height: usize) -> impl Iterator<Item = GridPosition> {
+    (0..height as i32).flat_map(move |y| (0..width as i32).map(move |x| GridPosition::new(x, y)))
+}
+
+/// 爆炸十字的四条臂（按 BLAST_DIRECTIONS 的顺序）：每个方向从中心旁边一格开始、由近到远的 range 个格子（不考虑阻挡）
+pub fn blast_arms(
+    center: GridPosition,
+    range: i32,
+) -> impl Iterator<Item = ((i32, i32), impl Iterator<Item = GridPosition>)> {
+    BLAST_DIRECTIONS
+        .into_iter()
+        .map(move |direction| (direction, (1..=range).map(move |i| center.offset(direction, i))))
+}
+
+/// 网格坐标与世界坐标的换算
+///
+/// 格子中心对应整数格子坐标；连续的格子坐标（cell space）用于玩家这类不对齐格子的移动。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridSpace {
+    /// 格子 (0, 0) 中心的世界坐标
+    pub origin: Vec2,
+    /// 每个格子的像素大小
+    pub cell_size: f32,
+}
+
+impl GridSpace {
+    /// 游戏地图使用的坐标系：格子 (0, 0) 的中心位于世界原点，与地图尺寸无关
+    pub const WORLD: Self = Self { origin: Vec2::ZERO, cell_size: CELL_SIZE };
+
+    /// 连续的格子坐标转世界坐标（y 轴反转）
+    pub fn cell_to_world(self, cell: Vec2) -> Vec2 {
+        Vec2::new(self.origin.x + cell.x * self.cell_size, self.origin.y - cell.y * self.cell_size)
+    }
+
+    /// 世界坐标转连续的格子坐标
+    pub fn world_to_cell(self, world: Vec2) -> Vec2 {
+        Vec2::new((world.x - self.origin.x) / self.cell_size, -(world.y - self.origin.y) / self.cell_size)
+    }
+
+    /// 格子中心的世界坐标
+    pub fn to_world(self, pos: GridPosition) -> Vec2 {
+        self.cell_to_world(IVec2::new(pos.x, pos.y).as_vec2())
+    }
+
+    /// 世界坐标所在的格子
+    pub fn to_grid(self, world: Vec2) -> GridPosition {
+        let cell = self.world_to_cell(world).round();
+        GridPosition::new(cell.x as i32, cell.y as i32)
+    }
+}
+
+/// 网格坐标转世界坐标（格子中心，z 为 0）
+pub fn grid_to_world(x: i32, y: i32) -> Vec3 {
+    GridSpace::WORLD.to_world(GridPosition::new(x, y)).extend(0.0)
+}
+
+/// 世界坐标转网格坐标
+pub fn world_to_grid(pos: Vec3) -> GridPosition {
+    GridSpace::WORLD.to_grid(pos.truncate())
+}
+
+/// 世界坐标转连续的格子坐标（格子中心为整数）
+pub fn world_to_cell_space(pos: Vec3) -> Vec2 {
+    GridSpace::WORLD.world_to_cell(pos.truncate())
+}
+
+/// 连续的格子坐标转世界坐标
+pub fn cell_space_to_world(cell: Vec2, z: f32) -> Vec3 {
+    GridSpace::WORLD.cell_to_world(cell).extend(z)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use super::*;
+
+    fn position() -> impl Strategy<Value = GridPosition> {
+        (-500..500, -500..500).prop_map(|(x, y)| GridPosition::new(x, y))
+    }
+
+    proptest! {
+        #[test]
+        fn world_round_trip(pos in position(), x in -1e4f32..1e4, y in -1e4f32..1e4, cell_size in 1f32..200.0) {
+            let space = GridSpace { origin: Vec2::new(x, y), cell_size };
+            prop_assert_eq!(space.to_grid(space.to_world(pos)), pos);
+        }
+
+        #[test]
+        fn manhattan_is_a_metric(a in position(), b in position(), c in position()) {
+            prop_assert_eq!(a.manhattan(b), b.manhattan(a));
+            prop_assert_eq!(a.manhattan(a), 0);
+            prop_assert!(a.manhattan(c) <= a.manhattan(b) + b.manhattan(c));
+        }
+
+        #[test]
+        fn neighbours_are_one_step_away(pos in position()) {
+            let neighbours: Vec<GridPosition> = pos.neighbours().collect();
+            prop_assert_eq!(neighbours.len(), 4);
+            for (i, next) in neighbours.iter().enumerate() {
+                prop_assert_eq!(next.manhattan(pos), 1);
+                prop_assert!(!neighbours[..i].contains(next));
+            }
+        }
+
+        #[test]
+        fn blast_arms_have_range_cells(center in position(), range in 0..20i32) {
+            let arms: Vec<((i32, i32), Vec<GridPosition>)> =
+                blast_arms(center, range).map(|(direction, cells)| (direction, cells.collect())).collect();
+            prop_assert_eq!(arms.len(), 4);
+            for (direction, cells) in arms {
+                prop_assert_eq!(cells.len(), range as usize);
+                for (i, cell) in cells.iter().enumerate() {
+                    prop_assert_eq!(*cell, center.offset(direction, i as i32 + 1));
+                }
+            }
+        }
+
+        #[test]
+        fn in_bounds_matches_cells(width in 0..30usize, height in 0..30usize, x in -5..35i32, y in -5..35i32) {
+            let all: Vec<GridPosition> = cells(width, height).collect();
+            prop_assert_eq!(all.len(), width * height);
+            prop_assert!(all.iter().all(|pos| in_bounds(*pos, width, height)));
+            let pos = GridPosition::new(x, y);
+            prop_assert_eq!(in_bounds(pos, width, height), all.contains(&pos));
+        }
+    }
+}
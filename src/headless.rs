@@ -0,0 +1,251 @@
+use std::time::{Duration, Instant};
+
+use bevy::{
+    asset::AssetPlugin,
+    ecs::schedule::ExecutorKind,
+    prelude::*,
+    time::TimeUpdateStrategy,
+    utils::HashSet,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use crate::{
+    animation::AnimationPlugin,
+    arena::ArenaPlugin,
+    bomb::BombPlugin,
+    constants::*,
+    enemy::EnemyPlugin,
+    level::{LevelData, LevelPlugin},
+    map::MapPlugin,
+    mapgen::{generate, MapGenConfig},
+    player::PlayerPlugin,
+    powerup::PowerUpPlugin,
+    resource::*,
+    score::ScorePlugin,
+    tilemap::TileMapPlugin,
+};
+
+/// 无窗口模拟：只用 MinimalPlugins 运行地图、玩家、炸弹和敌人逻辑
+///
+/// 玩法模块不依赖渲染和音频，不创建窗口，也不需要显卡和音频设备；没有 SoundPlugin 读取的音效事件会被自动丢弃。
+/// 键盘输入由 HeadlessInput 注入，时间按固定步长推进，同样的输入得到同样的帧序列。
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        // 关卡资源仍走 Assets<LevelData>，只需要 AssetPlugin，不会加载任何文件
+        app
+            .add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<HeadlessInput>()
+            .add_system(apply_headless_input.in_base_set(CoreSet::PreUpdate));
+    }
+}
+
+/// 注入的键盘输入：下一帧按住的键（不在集合中的键视为松开）
+#[derive(Resource, Default)]
+pub struct HeadlessInput(pub HashSet<KeyCode>);
+
+/// 按注入的输入更新键盘状态，和真实键盘一样产生“刚按下/刚松开”
+fn apply_headless_input(input: Res<HeadlessInput>, mut keyboard: ResMut<Input<KeyCode>>) {
+    keyboard.clear();
+    let released: Vec<KeyCode> = keyboard.get_pressed().filter(|key| !input.0.contains(key)).copied().collect();
+    for key in released {
+        keyboard.release(key);
+    }
+    for key in input.0.iter() {
+        keyboard.press(*key);
+    }
+}
+
+/// 模拟中的操作者：每一步根据当前世界决定按住哪些键
+pub trait Controller {
+    fn keys(&mut self, world: &mut World) -> HashSet<KeyCode>;
+}
+
+/// 一局的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundResult {
+    /// 消灭了所有敌人
+    Cleared,
+    /// 生命耗尽
+    Failed,
+    /// 对战模式本局结束（None 表示平局）
+    Versus(Option<usize>),
+    /// 达到步数上限仍未分出结果
+    Unfinished,
+}
+
+/// 一局的统计
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundOutcome {
+    pub result: RoundResult,
+    /// 模拟的游戏时间（秒）
+    pub seconds: f32,
+    pub score: u32,
+}
+
+/// 一局无窗口模拟
+pub struct Simulation {
+    app: App,
+    /// 手动推进的时钟，每步前进固定步长
+    clock: Instant,
+    step: Duration,
+}
+
+impl Simulation {
+    /// 用指定关卡和模式创建模拟，玩法中的随机数由 seed 决定，第一次 step 时进入关卡
+    pub fn new(level: LevelData, mode: GameMode, seed: u64, step: Duration) -> Self {
+        let mut app = App::new();
+        app
+            .add_plugin(HeadlessPlugin)
+            .add_state::<GameState>()
+            .init_resource::<GamePaused>()
+            .init_resource::<CurrentLevel>()
+            .init_resource::<Score>()
+            .init_resource::<LevelTimer>()
+            .insert_resource(mode)
+            .insert_resource(MatchScore::new(mode.players()))
+            .insert_resource(Lives::new(mode.players()))
+            .insert_resource(SimRng::seeded(seed))
+            .add_plugin(LevelPlugin)
+            .add_plugin(MapPlugin)
+            .add_plugin(PlayerPlugin)
+            .add_plugin(BombPlugin)
+            .add_plugin(EnemyPlugin)
+            .add_plugin(PowerUpPlugin)
+            .add_plugin(AnimationPlugin)
+            .add_plugin(ScorePlugin)
+            .add_plugin(ArenaPlugin)
+            .add_plugin(TileMapPlugin);
+
+        // 关卡放到当前模式读取的位置
+        let handle = app.world.resource_mut::<Assets<LevelData>>().add(level);
+        let mut handles = LevelHandles { campaign: Vec::new(), versus: Handle::default(), generated: None };
+        match mode {
+            GameMode::Campaign => handles.campaign.push(handle),
+            GameMode::Versus { .. } => handles.versus = handle,
+            GameMode::Random { .. } => handles.generated = Some(handle),
+        }
+        app.insert_resource(handles);
+        app.insert_resource(NextState(Some(GameState::InGame)));
+
+        // 多线程执行器中互不约束的系统先后次序不固定，它们共用的资源（如 SimRng）会因此产生不同结果
+        for (_, schedule) in app.world.resource_mut::<Schedules>().iter_mut() {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        }
+
+        Self { app, clock: Instant::now(), step }
+    }
+
+    /// 随机地图模式：按种子生成第一关
+    pub fn random(seed: u64, step: Duration) -> Self {
+        Self::new(generate(seed, &MapGenConfig::for_level(0)), GameMode::Random { seed }, seed, step)
+    }
+
+    /// 推进一帧
+    pub fn step(&mut self) {
+        self.clock += self.step;
+        self.app.insert_resource(TimeUpdateStrategy::ManualInstant(self.clock));
+        self.app.update();
+    }
+
+    pub fn state(&self) -> GameState {
+        self.app.world.resource::<State<GameState>>().0
+    }
+
+    /// 由操作者控制运行一局，直到离开关卡或达到步数上限
+    pub fn run_round(&mut self, controller: &mut impl Controller, max_steps: usize) -> RoundOutcome {
+        let mut steps = 0;
+        // 第一步进入关卡
+        self.step();
+        while self.state() == GameState::InGame && steps < max_steps {
+            let keys = controller.keys(&mut self.app.world);
+            self.app.insert_resource(HeadlessInput(keys));
+            self.step();
+            steps += 1;
+        }
+
+        let result = match self.state() {
+            GameState::Victory | GameState::CampaignComplete => RoundResult::Cleared,
+            GameState::GameOver => RoundResult::Failed,
+            GameState::RoundOver => RoundResult::Versus(self.app.world.resource::<MatchScore>().round_winner),
+            _ => RoundResult::Unfinished,
+        };
+        RoundOutcome {
+            result,
+            seconds: steps as f32 * self.step.as_secs_f32(),
+            score: self.app.world.resource::<Score>().total,
+        }
+    }
+}
+
+/// 随机操作的机器人：每隔一段时间换一个方向（或停下），偶尔放炸弹和遥控引爆
+pub struct RandomBot {
+    rng: StdRng,
+    held: Option<KeyCode>,
+    /// 距离下一次换方向还剩的步数
+    hold_steps: u32,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed), held: None, hold_steps: 0 }
+    }
+}
+
+impl Controller for RandomBot {
+    fn keys(&mut self, _world: &mut World) -> HashSet<KeyCode> {
+        if self.hold_steps == 0 {
+            self.held = [None, Some(KeyCode::W), Some(KeyCode::S), Some(KeyCode::A), Some(KeyCode::D)]
+                .choose(&mut self.rng)
+                .copied()
+                .flatten();
+            self.hold_steps = self.rng.gen_range(10..60);
+        }
+        self.hold_steps -= 1;
+
+        let mut keys: HashSet<KeyCode> = self.held.into_iter().collect();
+        if self.rng.gen_bool(HEADLESS_BOT_BOMB_CHANCE) {
+            keys.insert(KeyCode::Space);
+        }
+        if self.rng.gen_bool(HEADLESS_BOT_BOMB_CHANCE) {
+            keys.insert(KeyCode::E);
+        }
+        keys
+    }
+}
+
+/// `--headless` 入口：用随机机器人在随机地图上连续模拟多局，按顺序返回每局的结果
+///
+/// 第 i 局的地图种子、玩法随机数种子和机器人种子都是 seed + i，结果可以复现。
+pub fn run(rounds: usize, seed: u64) -> Vec<RoundOutcome> {
+    let step = Duration::from_secs_f32(HEADLESS_STEP);
+    let max_steps = (HEADLESS_MAX_SECONDS / HEADLESS_STEP) as usize;
+    (0..rounds as u64)
+        .map(|round| {
+            let round_seed = seed + round;
+            Simulation::random(round_seed, step).run_round(&mut RandomBot::new(round_seed), max_steps)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded_round(seed: u64) -> RoundOutcome {
+        let step = Duration::from_secs_f32(HEADLESS_STEP);
+        let max_steps = (HEADLESS_MAX_SECONDS / HEADLESS_STEP) as usize;
+        Simulation::random(seed, step).run_round(&mut RandomBot::new(seed), max_steps)
+    }
+
+    #[test]
+    fn seeded_round_is_reproducible() {
+        let outcome = seeded_round(1);
+        // 机器人最终会炸死自己，耗尽生命
+        assert_eq!(outcome.result, RoundResult::Failed);
+        assert!(outcome.seconds > 0.0);
+        assert_eq!(seeded_round(1), outcome);
+    }
+}
@@ -0,0 +1,539 @@
+use std::fmt;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use rand::Rng;
+use crate::{
+    components::{EnemyBehaviour, EnemyKind, GridPosition, PowerUp},
+    constants::*,
+    grid::in_bounds,
+    resource::{CurrentLevel, GameMode, LevelHandles},
+};
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_asset::<LevelData>()
+            .init_asset_loader::<LevelLoader>();
+    }
+}
+
+/// 地图格子类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tile {
+    #[default]
+    Empty,
+    Wall,
+    BreakableWall,
+}
+
+/// 关卡数据（从 assets/levels/*.level 加载）
+#[derive(Debug, TypeUuid)]
+#[uuid = "78fdb6d0-ddd5-4226-b782-644cbad3f765"]
+pub struct LevelData {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub tiles: Vec<Tile>,
+    /// 玩家出生点（按从上到下、从左到右的顺序，对应玩家1~4）
+    pub player_spawns: Vec<GridPosition>,
+    pub enemy_spawns: Vec<EnemySpawn>,
+    /// 覆盖 BOMB_TIMER
+    pub bomb_timer: f32,
+    /// 覆盖 EXPLOSION_RANGE
+    pub explosion_range: i32,
+    /// 可破坏墙的道具掉落表
+    pub drops: DropTable,
+    /// 本关玩家开局即拥有遥控引爆能力
+    pub remote_bombs: bool,
+    /// 本关时间限制（秒）
+    pub time_limit: f32,
+}
+
+/// 敌人出生点
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnemySpawn {
+    pub pos: GridPosition,
+    pub kind: EnemyKind,
+    /// 覆盖这个敌人的AI策略（None 表示使用敌人类型的默认策略）
+    pub behaviour: Option<EnemyBehaviour>,
+}
+
+impl EnemySpawn {
+    pub fn new(pos: GridPosition, kind: EnemyKind) -> Self {
+        Self { pos, kind, behaviour: None }
+    }
+}
+
+impl LevelData {
+    /// 获取指定格子的类型（越界视为不可破坏墙）
+    pub fn tile(&self, x: i32, y: i32) -> Tile {
+        if !in_bounds(GridPosition::new(x, y), self.width, self.height) {
+            return Tile::Wall;
+        }
+        self.tiles[y as usize * self.width + x as usize]
+    }
+}
+
+/// 道具掉落表
+#[derive(Debug, Clone)]
+pub struct DropTable {
+    /// 可破坏墙被炸毁时掉落道具的概率（0~1）
+    pub chance: f32,
+    /// 各道具的掉落权重
+    pub weights: Vec<(PowerUp, u32)>,
+}
+
+impl Default for DropTable {
+    fn default() -> Self {
+        let kinds = [
+            PowerUp::BombUp,
+            PowerUp::FireUp,
+            PowerUp::SpeedUp,
+            PowerUp::Kick,
+            PowerUp::Remote,
+            PowerUp::Pierce,
+            PowerUp::LineBomb,
+        ];
+        Self {
+            chance: DEFAULT_DROP_CHANCE,
+            weights: kinds.into_iter().zip(DEFAULT_DROP_WEIGHTS).collect(),
+        }
+    }
+}
+
+impl DropTable {
+    fn set_weight(&mut self, kind: PowerUp, weight: u32) {
+        if let Some(entry) = self.weights.iter_mut().find(|(k, _)| *k == kind) {
+            entry.1 = weight;
+        }
+    }
+
+    /// 按概率和权重抽取一个道具
+    pub fn roll(&self, rng: &mut impl Rng) -> Option<PowerUp> {
+        let total: u32 = self.weights.iter().map(|(_, weight)| weight).sum();
+        if total == 0 || !rng.gen_bool(self.chance as f64) {
+            return None;
+        }
+
+        let mut pick = rng.gen_range(0..total);
+        for &(kind, weight) in self.weights.iter() {
+            if pick < weight {
+                return Some(kind);
+            }
+            pick -= weight;
+        }
+        None
+    }
+}
+
+/// 关卡用途（头部 `mode` 键）：战役关卡必须有敌人出生点，对战地图不需要
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelKind {
+    Campaign,
+    Versus,
+}
+
+/// 关卡文件解析错误（带行号和列号，均从1开始）
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl LevelParseError {
+    fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self { line, column, message: message.into() }
+    }
+}
+
+impl fmt::Display for LevelParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for LevelParseError {}
+
+/// 解析关卡文本
+///
+/// 文件由头部键值对和 `[map]` 段组成，`//` 开头的行为注释：
+/// - `name = ...` 关卡名称
+/// - `mode = campaign|versus` 关卡用途，默认 campaign
+/// - `bomb_timer = 3.0` 炸弹爆炸时间（秒）
+/// - `explosion_range = 2` 爆炸范围（格子数）
+/// - `drop_chance = 0.3` 可破坏墙掉落道具的概率
+/// - `drop_bomb` / `drop_fire` / `drop_speed` / `drop_kick` / `drop_remote` / `drop_pierce` / `drop_line` 各道具的掉落权重
+/// - `enemy_ai = <x>,<y> wander|chase|evade|hunter` 覆盖该坐标敌人出生点的AI策略（可写多行）
+/// - `remote_bombs = true|false` 玩家开局即拥有遥控引爆能力
+/// - `time_limit = 180` 时间限制（秒），耗尽后场地开始缩圈
+///
+/// `[map]` 段的每一行是地图的一行，行数和列数即地图尺寸（可以不是正方形），各行必须等宽。
+///
+/// 地图字符：`#`=不可破坏墙, `*`=可破坏墙, `.`=空地, `P`=玩家出生点（最多4个）,
+/// 敌人出生点：`E`/`W`=游走者, `C`=追踪者, `G`=幽灵, `T`=坦克
+///
+/// 至少需要一个玩家出生点；战役关卡（`mode = campaign`）还至少需要一个敌人出生点。
+pub fn parse_level(source: &str) -> Result<LevelData, LevelParseError> {
+    let mut name = String::new();
+    let mut kind = LevelKind::Campaign;
+    let mut bomb_timer = BOMB_TIMER;
+    let mut explosion_range = EXPLOSION_RANGE;
+    let mut drops = DropTable::default();
+    // (行号, 值所在列, 出生点, 策略)，地图解析完后再对应到出生点
+    let mut behaviours: Vec<(usize, usize, GridPosition, EnemyBehaviour)> = Vec::new();
+    let mut remote_bombs = false;
+    let mut time_limit = DEFAULT_TIME_LIMIT;
+    let mut rows: Vec<(usize, &str)> = Vec::new();
+    let mut in_map = false;
+    let mut line_count = 0;
+
+    for (index, raw_line) in source.lines().enumerate() {
+        let line_no = index + 1;
+        line_count = line_no;
+        let line = raw_line.trim_end();
+        let trimmed = line.trim_start();
+
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        if in_map {
+            rows.push((line_no, line));
+            continue;
+        }
+
+        if trimmed == "[map]" {
+            in_map = true;
+            continue;
+        }
+
+        let Some(eq) = line.find('=') else {
+            return Err(LevelParseError::new(
+                line_no,
+                line.len() - trimmed.len() + 1,
+                "expected `key = value` or `[map]`",
+            ));
+        };
+        let key = line[..eq].trim();
+        let value = line[eq + 1..].trim();
+        // 值的起始列，用于报告数值解析错误
+        let value_column = eq + 2 + (line[eq + 1..].len() - line[eq + 1..].trim_start().len());
+
+        let number = |valid: fn(f64) -> bool, expected: &str| {
+            value.parse::<f64>().ok().filter(|v| valid(*v)).ok_or_else(|| {
+                LevelParseError::new(line_no, value_column, format!("invalid {key} `{value}`, expected {expected}"))
+            })
+        };
+
+        match key {
+            "name" => name = value.to_owned(),
+            "mode" => {
+                kind = match value {
+                    "campaign" => LevelKind::Campaign,
+                    "versus" => LevelKind::Versus,
+                    _ => {
+                        return Err(LevelParseError::new(
+                            line_no,
+                            value_column,
+                            format!("invalid mode `{value}`, expected campaign or versus"),
+                        ));
+                    }
+                };
+            }
+            "bomb_timer" => bomb_timer = number(|v| v.is_finite() && v > 0.0, "a positive number")? as f32,
+            "explosion_range" => {
+                explosion_range = number(|v| v >= 1.0 && v.fract() == 0.0, "an integer >= 1")? as i32;
+            }
+            "drop_chance" => drops.chance = number(|v| (0.0..=1.0).contains(&v), "a number between 0 and 1")? as f32,
+            "drop_bomb" | "drop_fire" | "drop_speed" | "drop_kick" | "drop_remote" | "drop_pierce" | "drop_line" => {
+                let weight = number(|v| v >= 0.0 && v.fract() == 0.0, "a non-negative integer")? as u32;
+                let kind = match key {
+                    "drop_bomb" => PowerUp::BombUp,
+                    "drop_fire" => PowerUp::FireUp,
+                    "drop_speed" => PowerUp::SpeedUp,
+                    "drop_kick" => PowerUp::Kick,
+                    "drop_remote" => PowerUp::Remote,
+                    "drop_pierce" => PowerUp::Pierce,
+                    _ => PowerUp::LineBomb,
+                };
+                drops.set_weight(kind, weight);
+            }
+            "enemy_ai" => {
+                let invalid = || {
+                    LevelParseError::new(
+                        line_no,
+                        value_column,
+                        format!("invalid enemy_ai `{value}`, expected `<x>,<y> wander|chase|evade|hunter`"),
+                    )
+                };
+                let (pos, strategy) = value.split_once(char::is_whitespace).ok_or_else(invalid)?;
+                let (x, y) = pos.split_once(',').ok_or_else(invalid)?;
+                let pos = match (x.trim().parse(), y.trim().parse()) {
+                    (Ok(x), Ok(y)) => GridPosition::new(x, y),
+                    _ => return Err(invalid()),
+                };
+                let behaviour = match strategy.trim() {
+                    "wander" => EnemyBehaviour::Wander,
+                    "chase" => EnemyBehaviour::Chase,
+                    "evade" => EnemyBehaviour::Evade,
+                    "hunter" => EnemyBehaviour::Hunter,
+                    _ => return Err(invalid()),
+                };
+                behaviours.push((line_no, value_column, pos, behaviour));
+            }
+            "time_limit" => time_limit = number(|v| v.is_finite() && v > 0.0, "a positive number")? as f32,
+            "remote_bombs" => {
+                remote_bombs = value.parse::<bool>().map_err(|_| {
+                    LevelParseError::new(
+                        line_no,
+                        value_column,
+                        format!("invalid remote_bombs `{value}`, expected true or false"),
+                    )
+                })?;
+            }
+            _ => {
+                return Err(LevelParseError::new(
+                    line_no,
+                    line.len() - trimmed.len() + 1,
+                    format!("unknown key `{key}`"),
+                ));
+            }
+        }
+    }
+
+    if !in_map {
+        return Err(LevelParseError::new(line_count.max(1), 1, "missing `[map]` section"));
+    }
+
+    // 地图尺寸由第一行的宽度和行数决定，每一行必须一样宽
+    let Some((_, first_row)) = rows.first() else {
+        return Err(LevelParseError::new(line_count.max(1), 1, "map has no rows"));
+    };
+    let height = rows.len();
+    let width = first_row.chars().count();
+    let mut tiles = Vec::with_capacity(width * height);
+    let mut player_spawns = Vec::new();
+    let mut enemy_spawns = Vec::new();
+
+    for (y, (line_no, row)) in rows.iter().enumerate() {
+        let row_width = row.chars().count();
+        if row_width != width {
+            return Err(LevelParseError::new(
+                *line_no,
+                row_width.min(width) + 1,
+                format!("map row has {row_width} columns, expected {width}"),
+            ));
+        }
+
+        for (x, ch) in row.chars().enumerate() {
+            let pos = GridPosition::new(x as i32, y as i32);
+            let tile = match ch {
+                '#' => Tile::Wall,
+                '*' => Tile::BreakableWall,
+                '.' => Tile::Empty,
+                'P' => {
+                    if player_spawns.len() >= MAX_PLAYERS {
+                        return Err(LevelParseError::new(*line_no, x + 1, format!("more than {MAX_PLAYERS} player spawns `P`")));
+                    }
+                    player_spawns.push(pos);
+                    Tile::Empty
+                }
+                'E' | 'W' | 'C' | 'G' | 'T' => {
+                    let kind = match ch {
+                        'C' => EnemyKind::Chaser,
+                        'G' => EnemyKind::Ghost,
+                        'T' => EnemyKind::Tank,
+                        _ => EnemyKind::Wanderer,
+                    };
+                    enemy_spawns.push(EnemySpawn::new(pos, kind));
+                    Tile::Empty
+                }
+                other => {
+                    return Err(LevelParseError::new(*line_no, x + 1, format!("unknown map tile `{other}`")));
+                }
+            };
+            tiles.push(tile);
+        }
+    }
+
+    let map_start = rows[0].0;
+    if player_spawns.is_empty() {
+        return Err(LevelParseError::new(map_start, 1, "map has no player spawn `P`"));
+    }
+    if kind == LevelKind::Campaign && enemy_spawns.is_empty() {
+        return Err(LevelParseError::new(map_start, 1, "map has no enemy spawn (`E`, `W`, `C`, `G` or `T`)"));
+    }
+
+    for (line_no, column, pos, behaviour) in behaviours {
+        let Some(spawn) = enemy_spawns.iter_mut().find(|spawn| spawn.pos == pos) else {
+            return Err(LevelParseError::new(
+                line_no,
+                column,
+                format!("enemy_ai position {},{} is not an enemy spawn", pos.x, pos.y),
+            ));
+        };
+        spawn.behaviour = Some(behaviour);
+    }
+
+    Ok(LevelData {
+        name,
+        width,
+        height,
+        tiles,
+        player_spawns,
+        enemy_spawns,
+        bomb_timer,
+        explosion_range,
+        drops,
+        remote_bombs,
+        time_limit,
+    })
+}
+
+/// 关卡文件加载器
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let source = std::str::from_utf8(bytes)?;
+            let level = parse_level(source)?;
+            load_context.set_default_asset(LoadedAsset::new(level));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level"]
+    }
+}
+
+/// 关卡难度（随关卡序号递增）
+#[derive(Debug, Clone, Copy)]
+pub struct Difficulty {
+    /// 敌人每次移动的间隔（秒）
+    pub enemy_move_interval: f32,
+}
+
+impl Difficulty {
+    pub fn for_level(index: usize) -> Self {
+        let interval = ENEMY_MOVE_INTERVAL * ENEMY_SPEEDUP_PER_LEVEL.powi(index as i32);
+        Self {
+            enemy_move_interval: interval.max(ENEMY_MIN_MOVE_INTERVAL),
+        }
+    }
+}
+
+/// 当前关卡数据（关卡尚未加载完成时返回 None）
+#[derive(SystemParam)]
+pub struct ActiveLevel<'w> {
+    mode: Res<'w, GameMode>,
+    current: Res<'w, CurrentLevel>,
+    handles: Res<'w, LevelHandles>,
+    levels: Res<'w, Assets<LevelData>>,
+}
+
+impl<'w> ActiveLevel<'w> {
+    pub fn get(&self) -> Option<&LevelData> {
+        match *self.mode {
+            GameMode::Campaign => self.handles.campaign.get(self.current.0).and_then(|handle| self.levels.get(handle)),
+            GameMode::Versus { .. } => self.levels.get(&self.handles.versus),
+            GameMode::Random { .. } => self.handles.generated.as_ref().and_then(|handle| self.levels.get(handle)),
+        }
+    }
+
+    /// 当前关卡序号（从0开始）
+    pub fn index(&self) -> usize {
+        self.current.0
+    }
+
+    /// 是否为战役最后一关（随机地图模式没有最后一关）
+    pub fn is_last(&self) -> bool {
+        match *self.mode {
+            GameMode::Random { .. } => false,
+            _ => self.current.0 + 1 >= self.handles.campaign.len(),
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::for_level(self.current.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "[map]\n#####\n#P.E#\n#.#.#\n#..C#\n#####\n";
+
+    #[test]
+    fn enemy_ai_overrides_single_spawn() {
+        let level = parse_level(&format!("enemy_ai = 3,3 hunter\n{MAP}")).unwrap();
+        let behaviours: Vec<_> = level.enemy_spawns.iter().map(|spawn| (spawn.kind, spawn.behaviour)).collect();
+        assert_eq!(
+            behaviours,
+            vec![(EnemyKind::Wanderer, None), (EnemyKind::Chaser, Some(EnemyBehaviour::Hunter))]
+        );
+    }
+
+    #[test]
+    fn enemy_ai_must_name_an_enemy_spawn() {
+        let error = parse_level(&format!("enemy_ai = 1,1 chase\n{MAP}")).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 12));
+
+        let error = parse_level(&format!("enemy_ai = hunter\n{MAP}")).unwrap_err();
+        assert_eq!(error.line, 1);
+    }
+
+    #[test]
+    fn unknown_tile_reports_its_column() {
+        let error = parse_level("[map]\n#####\n#P.X#\n#####\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 4));
+    }
+
+    #[test]
+    fn ragged_map_row_reports_first_missing_column() {
+        let error = parse_level("[map]\n#####\n#P.E\n#####\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 5));
+    }
+
+    #[test]
+    fn missing_map_section_reports_last_line() {
+        let error = parse_level("name = Empty\nbomb_timer = 2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+
+    #[test]
+    fn non_finite_numbers_are_rejected() {
+        let error = parse_level(&format!("bomb_timer = inf\n{MAP}")).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 14));
+
+        let error = parse_level(&format!("name = Endless\ntime_limit = inf\n{MAP}")).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 14));
+    }
+
+    #[test]
+    fn only_campaign_levels_need_enemies() {
+        let map = "[map]\n#####\n#P.P#\n#####\n";
+        let error = parse_level(map).unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(parse_level(&format!("mode = versus\n{map}")).unwrap().enemy_spawns.is_empty());
+
+        let error = parse_level(&format!("mode = duel\n{map}")).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 8));
+    }
+}
@@ -0,0 +1,28 @@
+//! 炸弹人游戏的玩法和界面模块，由 main.rs 组装成窗口程序，也可以在无窗口模拟和基准测试中直接使用
+//!
+//! 精灵、界面、摄像机和音频只在 graphics 特性（默认开启）下编译，玩法模块不依赖它们。
+
+pub mod animation;
+pub mod arena;
+#[cfg(feature = "graphics")]
+pub mod camera;
+pub mod components;
+pub mod constants;
+pub mod map;
+pub mod mapgen;
+pub mod pathfinding;
+pub mod player;
+pub mod powerup;
+pub mod bomb;
+pub mod enemy;
+pub mod grid;
+pub mod headless;
+pub mod level;
+pub mod resource;
+pub mod score;
+pub mod sound;
+#[cfg(feature = "graphics")]
+pub mod sprites;
+pub mod tilemap;
+#[cfg(feature = "graphics")]
+pub mod ui;
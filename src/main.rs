@@ -1,23 +1,42 @@
+#[cfg(feature = "graphics")]
 use bevy::prelude::*;
-
-mod components;
-mod constants;
-mod map;
-mod player;
-mod bomb;
-mod enemy;
-mod resource;
-mod ui;
-
-use constants::*;
-use map::MapPlugin;
-use player::PlayerPlugin;
-use bomb::BombPlugin;
-use enemy::EnemyPlugin;
-use resource::{GameState, GameTextures, GameAudio, GamePaused};
-use ui::UIPlugin;
+use bomberman::{
+    constants::*,
+    headless::{self, RoundResult},
+};
+#[cfg(feature = "graphics")]
+use bomberman::{
+    animation::AnimationPlugin,
+    arena::ArenaPlugin,
+    camera::CameraPlugin,
+    components::{MainCamera, ScreenSpace},
+    map::MapPlugin,
+    player::PlayerPlugin,
+    powerup::PowerUpPlugin,
+    bomb::BombPlugin,
+    enemy::EnemyPlugin,
+    level::LevelPlugin,
+    resource::{GameState, GameTextures, GameAudio, GamePaused, CurrentLevel, LevelHandles, GameMode, MatchScore, Lives, Score, LevelTimer, SeedArg, SimRng},
+    score::ScorePlugin,
+    sound::SoundPlugin,
+    sprites::SpritesPlugin,
+    tilemap::TileMapPlugin,
+    ui::UIPlugin,
+};
 
 fn main() {
+    // 无窗口模拟：不打开窗口，跑完指定局数后退出（没有 graphics 特性时总是无窗口运行）
+    if cfg!(not(feature = "graphics")) || std::env::args().any(|arg| arg == "--headless") {
+        run_headless(arg_value("--rounds").unwrap_or(HEADLESS_ROUNDS), seed_arg().unwrap_or(0));
+    } else {
+        #[cfg(feature = "graphics")]
+        run_windowed();
+    }
+}
+
+/// 打开窗口运行游戏
+#[cfg(feature = "graphics")]
+fn run_windowed() {
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
@@ -35,25 +54,48 @@ fn main() {
             COLOR_BACKGROUND.2,
         )))
         .init_resource::<GamePaused>()
+        .init_resource::<CurrentLevel>()
+        .init_resource::<GameMode>()
+        .init_resource::<MatchScore>()
+        .init_resource::<Lives>()
+        .init_resource::<Score>()
+        .init_resource::<LevelTimer>()
+        .init_resource::<SimRng>()
+        .insert_resource(SeedArg {
+            seed: seed_arg(),
+            // 命令行和HUD上的关卡从1开始
+            stage: arg_value::<usize>("--stage").map_or(0, |stage| stage.saturating_sub(1)),
+        })
         .add_startup_system(setup)
         .add_startup_system(setup_audio)
         .add_startup_system(setup_background)
+        .add_startup_system(setup_level)
+        .add_plugin(LevelPlugin)
         .add_plugin(MapPlugin)
         .add_plugin(PlayerPlugin)
         .add_plugin(BombPlugin)
         .add_plugin(EnemyPlugin)
+        .add_plugin(PowerUpPlugin)
         .add_plugin(UIPlugin)
+        .add_plugin(AnimationPlugin)
+        .add_plugin(ScorePlugin)
+        .add_plugin(ArenaPlugin)
+        .add_plugin(TileMapPlugin)
+        .add_plugin(CameraPlugin)
+        .add_plugin(SpritesPlugin)
+        .add_plugin(SoundPlugin)
         .run();
 }
 
 /// 初始化设置 - 加载TextureAtlas精灵图集
+#[cfg(feature = "graphics")]
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
 ) {
     // 设置摄像机
-    commands.spawn(Camera2dBundle::default());
+    commands.spawn((Camera2dBundle::default(), MainCamera));
 
     // 加载玩家精灵图集 (14列×4行 = 56帧)
     let player_texture_handle = asset_server.load(PLAYER_SPRITE);
@@ -94,6 +136,7 @@ fn setup(
 }
 
 /// 加载游戏音频资源
+#[cfg(feature = "graphics")]
 fn setup_audio(mut commands: Commands, asset_server: Res<AssetServer>) {
     let game_audio = GameAudio {
         game_over: asset_server.load(AUDIO_GAME_OVER),
@@ -102,21 +145,84 @@ fn setup_audio(mut commands: Commands, asset_server: Res<AssetServer>) {
         bomb_place: asset_server.load(AUDIO_BOMB_PLACE),
         victory: asset_server.load(AUDIO_VICTORY),
         bomb_explosion: asset_server.load(AUDIO_BOMB_EXPLOSION),
+        music: asset_server.load(AUDIO_MUSIC),
     };
     
     commands.insert_resource(game_audio);
 }
 
+/// 加载战役关卡和对战地图文件
+#[cfg(feature = "graphics")]
+fn setup_level(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(LevelHandles {
+        campaign: LEVEL_PATHS.iter().map(|path| asset_server.load(*path)).collect(),
+        versus: asset_server.load(VERSUS_LEVEL_PATH),
+        generated: None,
+    });
+}
+
+/// 读取命令行参数 `--seed <n>`（随机地图种子）
+fn seed_arg() -> Option<u64> {
+    arg_value("--seed")
+}
+
+/// 读取命令行参数 `<name> <value>`，值无效时报错退出（此时还没有日志插件）
+fn arg_value<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let args: Vec<String> = std::env::args().collect();
+    let value = args.windows(2).find(|pair| pair[0] == name)?.get(1)?;
+    match value.parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            eprintln!("invalid {} `{}`", name, value);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// 运行无窗口模拟，打印每局结果和汇总
+fn run_headless(rounds: usize, seed: u64) {
+    let outcomes = headless::run(rounds, seed);
+    let (mut cleared, mut failed, mut unfinished) = (0, 0, 0);
+    let mut seconds = 0.0;
+
+    for (round, outcome) in outcomes.iter().enumerate() {
+        println!(
+            "round {} seed {}: {:?} in {:.1}s, score {}",
+            round + 1,
+            seed + round as u64,
+            outcome.result,
+            outcome.seconds,
+            outcome.score
+        );
+        match outcome.result {
+            RoundResult::Cleared => cleared += 1,
+            RoundResult::Failed => failed += 1,
+            RoundResult::Versus(_) | RoundResult::Unfinished => unfinished += 1,
+        }
+        seconds += outcome.seconds;
+    }
+
+    println!(
+        "{} rounds: {} cleared, {} failed, {} unfinished, {:.1}s simulated",
+        rounds, cleared, failed, unfinished, seconds
+    );
+}
+
 /// 设置背景精灵，填充窗口
+#[cfg(feature = "graphics")]
 fn setup_background(mut commands: Commands, asset_server: Res<AssetServer>) {
     let texture = asset_server.load(BACKGROUND_SPRITE);
-    commands.spawn(SpriteBundle {
-        texture,
-        sprite: Sprite {
-            custom_size: Some(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT)),
+    commands.spawn((
+        SpriteBundle {
+            texture,
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, -10.0),
             ..Default::default()
         },
-        transform: Transform::from_xyz(0.0, 0.0, -10.0),
-        ..Default::default()
-    });
+        // 背景固定在屏幕上，不随地图滚动
+        ScreenSpace::default(),
+    ));
 }
@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 use crate::{
     components::*,
-    constants::*,
-    resource::{GameState, GameTextures},
+    grid::grid_to_world,
+    level::{ActiveLevel, Tile},
+    resource::GameState,
 };
 
 pub struct MapPlugin;
@@ -13,64 +14,50 @@ impl Plugin for MapPlugin {
     }
 }
 
-/// 设置地图
-fn setup_map(mut commands: Commands, game_textures: Res<GameTextures>) {
-    // 地图布局：0=空地, 1=不可破坏墙, 2=可破坏墙（零散分布）
-    let map_layout = [
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-        [1, 0, 0, 2, 0, 2, 0, 0, 2, 0, 0, 0, 1],
-        [1, 0, 1, 0, 1, 0, 1, 2, 1, 0, 1, 0, 1],
-        [1, 0, 2, 0, 0, 0, 0, 0, 0, 2, 0, 0, 1],
-        [1, 0, 1, 0, 1, 0, 1, 0, 1, 2, 1, 0, 1],
-        [1, 2, 0, 0, 2, 0, 0, 0, 0, 0, 2, 0, 1],
-        [1, 0, 1, 0, 1, 0, 1, 2, 1, 0, 1, 2, 1],
-        [1, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0, 0, 1],
-        [1, 2, 1, 2, 1, 0, 1, 0, 1, 0, 1, 0, 1],
-        [1, 0, 0, 0, 0, 2, 0, 0, 0, 2, 2, 0, 1],
-        [1, 0, 1, 0, 1, 0, 1, 0, 1, 2, 1, 0, 1],
-        [1, 0, 0, 0, 2, 0, 0, 2, 0, 0, 0, 0, 1],
-        [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
-    ];
+/// 设置地图（根据当前关卡数据生成墙体）
+fn setup_map(mut commands: Commands, level: ActiveLevel) {
+    let Some(level) = level.get() else {
+        error!("level data is not loaded, map cannot be built");
+        return;
+    };
+    info!("building map for {}", level.name);
 
-    for (y, row) in map_layout.iter().enumerate() {
-        for (x, &cell) in row.iter().enumerate() {
-            let world_pos = grid_to_world(x as i32, y as i32);
-            
-            match cell {
-                1 => {
+    for y in 0..level.height as i32 {
+        for x in 0..level.width as i32 {
+            let world_pos = grid_to_world(x, y);
+
+            match level.tile(x, y) {
+                Tile::Wall => {
                     // 不可破坏墙 - 使用索引5
-                    spawn_wall(&mut commands, &game_textures, x as i32, y as i32, world_pos, false, 5);
+                    spawn_wall(&mut commands, x, y, world_pos, false, 5);
                 }
-                2 => {
+                Tile::BreakableWall => {
                     // 可破坏墙 - 使用索引3
-                    spawn_wall(&mut commands, &game_textures, x as i32, y as i32, world_pos, true, 3);
+                    spawn_wall(&mut commands, x, y, world_pos, true, 3);
                 }
-                _ => {} // 空地
+                Tile::Empty => {} // 空地
             }
         }
     }
 }
 
-/// 生成墙体（使用TextureAtlasSprite）
-fn spawn_wall(
+/// 生成墙体（wall.png 图集的 sprite_index 帧）
+pub fn spawn_wall(
     commands: &mut Commands,
-    game_textures: &GameTextures,
     x: i32,
     y: i32,
     world_pos: Vec3,
     breakable: bool,
     sprite_index: usize,
 ) {
-    let mut entity = commands.spawn(SpriteSheetBundle {
-        texture_atlas: game_textures.wall.clone(),
-        sprite: TextureAtlasSprite::new(sprite_index),
-        transform: Transform {
+    let mut entity = commands.spawn((
+        TransformBundle::from_transform(Transform {
             translation: world_pos,
             scale: Vec3::splat(3.5), // 放大精灵以适应60x60格子 (16*3.75=60) 
             ..default()
-        },
-        ..default()
-    });
+        }),
+        SpriteFrame::new(Sheet::Wall, sprite_index),
+    ));
 
     entity.insert(GridPosition::new(x, y));
 
@@ -81,21 +68,26 @@ fn spawn_wall(
     }
 }
 
-/// 网格坐标转世界坐标
-pub fn grid_to_world(x: i32, y: i32) -> Vec3 {
-    let offset = -(GRID_SIZE as f32 * CELL_SIZE) / 2.0 + CELL_SIZE / 2.0;//cell_size是因为格子中心到格子边框
-    Vec3::new(
-        x as f32 * CELL_SIZE + offset,
-        -(y as f32 * CELL_SIZE + offset), // Y轴反转
-        0.0,
-    )
-}
+/// 从边界墙内侧一圈开始，顺时针螺旋向中心排列的所有格子（用于缩圈）
+pub fn spiral_cells(width: usize, height: usize) -> Vec<GridPosition> {
+    let mut cells = Vec::new();
+    let (mut left, mut top) = (1, 1);
+    let (mut right, mut bottom) = (width as i32 - 2, height as i32 - 2);
+
+    while left <= right && top <= bottom {
+        cells.extend((left..=right).map(|x| GridPosition::new(x, top)));
+        cells.extend((top + 1..=bottom).map(|y| GridPosition::new(right, y)));
+        if top < bottom {
+            cells.extend((left..right).rev().map(|x| GridPosition::new(x, bottom)));
+        }
+        if left < right {
+            cells.extend((top + 1..bottom).rev().map(|y| GridPosition::new(left, y)));
+        }
+        left += 1;
+        top += 1;
+        right -= 1;
+        bottom -= 1;
+    }
 
-/// 世界坐标转网格坐标
-pub fn world_to_grid(pos: Vec3) -> GridPosition {
-    let offset = -(GRID_SIZE as f32 * CELL_SIZE) / 2.0 + CELL_SIZE / 2.0;
-    GridPosition::new(
-        ((pos.x - offset) / CELL_SIZE).round() as i32,
-        ((-pos.y - offset) / CELL_SIZE).round() as i32,
-    )
+    cells
 }
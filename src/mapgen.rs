@@ -0,0 +1,231 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, utils::HashSet};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use crate::{
+    components::{EnemyKind, GridPosition},
+    constants::*,
+    grid::{cells, in_bounds},
+    level::{DropTable, EnemySpawn, LevelData, Tile},
+    resource::LevelHandles,
+};
+
+/// 随机地图的生成参数
+#[derive(Debug, Clone)]
+pub struct MapGenConfig {
+    /// 地图尺寸（偶数会加一，保证外墙和柱子的排列完整）
+    pub width: usize,
+    pub height: usize,
+    /// 可破坏墙占空地的比例（0~1）
+    pub breakable_density: f32,
+    /// 敌人数量（可用的出生点不够时会少于这个数）
+    pub enemies: usize,
+}
+
+impl MapGenConfig {
+    /// 随机地图模式第 index 关（从0开始）的参数：每关多一个敌人
+    pub fn for_level(index: usize) -> Self {
+        Self {
+            width: RANDOM_MAP_WIDTH,
+            height: RANDOM_MAP_HEIGHT,
+            breakable_density: RANDOM_MAP_DENSITY,
+            enemies: RANDOM_MAP_ENEMIES + index,
+        }
+    }
+}
+
+/// 生成一个新的随机种子（取 u32 范围，方便在HUD上显示和口头分享）
+pub fn new_seed() -> u64 {
+    rand::thread_rng().gen::<u32>() as u64
+}
+
+/// 按种子生成地图：同一个种子和参数总是生成同一张地图
+///
+/// 使用算法固定的 ChaCha8Rng（StdRng 的算法可能随 rand 版本改变），分享出去的种子换个版本也能得到同一张地图。
+///
+/// - 外圈和坐标都为偶数的格子是不可破坏墙（经典的柱子排列）
+/// - 玩家在左上角出生，其余三个角落放敌人；每个角落的出生点和相邻两格保持空地，开局不会被困住
+/// - 其余空地按密度随机放置可破坏墙，额外的敌人放在离玩家足够远的空地上
+/// - 敌人出生点只从可破坏墙全部炸开后与玩家连通的格子中选择
+pub fn generate(seed: u64, config: &MapGenConfig) -> LevelData {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let width = config.width.max(5) | 1;
+    let height = config.height.max(5) | 1;
+    let index = |pos: GridPosition| pos.y as usize * width + pos.x as usize;
+
+    let mut tiles: Vec<Tile> = cells(width, height)
+        .map(|pos| {
+            let border = pos.x == 0 || pos.y == 0 || pos.x as usize == width - 1 || pos.y as usize == height - 1;
+            if border || (pos.x % 2 == 0 && pos.y % 2 == 0) {
+                Tile::Wall
+            } else {
+                Tile::Empty
+            }
+        })
+        .collect();
+
+    // 四个角落：左上为玩家，其余为敌人
+    let (right, bottom) = (width as i32 - 2, height as i32 - 2);
+    let player = GridPosition::new(1, 1);
+    let corners = [GridPosition::new(right, 1), GridPosition::new(1, bottom), GridPosition::new(right, bottom)];
+
+    // 角落出生点及其朝地图内侧的两个相邻格子
+    let mut reserved: HashSet<GridPosition> = HashSet::new();
+    for corner in std::iter::once(player).chain(corners) {
+        let dx = if corner.x == 1 { 1 } else { -1 };
+        let dy = if corner.y == 1 { 1 } else { -1 };
+        reserved.extend([corner, corner.step((dx, 0)), corner.step((0, dy))]);
+    }
+
+    // 所有非不可破坏墙的格子在可破坏墙炸开后都能走到，从中选择敌人出生点
+    let reachable = reachable_from(player, width, height, |pos| tiles[index(pos)] != Tile::Wall);
+
+    let mut enemy_spawns: Vec<GridPosition> =
+        corners.into_iter().filter(|pos| reachable.contains(pos)).take(config.enemies).collect();
+    let mut candidates: Vec<GridPosition> = cells(width, height)
+        .filter(|pos| reachable.contains(pos) && !reserved.contains(pos))
+        .filter(|pos| pos.manhattan(player) >= RANDOM_MAP_SAFE_DISTANCE)
+        .collect();
+    candidates.shuffle(&mut rng);
+    for pos in candidates.into_iter().take(config.enemies.saturating_sub(enemy_spawns.len())) {
+        // 额外的敌人也保留出生点和一个相邻格子，避免一出生就被墙围住
+        reserved.insert(pos);
+        if let Some(free) = pos.neighbours().find(|next| tiles[index(*next)] == Tile::Empty) {
+            reserved.insert(free);
+        }
+        enemy_spawns.push(pos);
+    }
+
+    for pos in cells(width, height) {
+        if tiles[index(pos)] == Tile::Empty && !reserved.contains(&pos) && rng.gen::<f32>() < config.breakable_density {
+            tiles[index(pos)] = Tile::BreakableWall;
+        }
+    }
+
+    let enemy_spawns = enemy_spawns.into_iter().map(|pos| EnemySpawn::new(pos, random_enemy(&mut rng))).collect();
+
+    LevelData {
+        name: format!("Random {seed}"),
+        width,
+        height,
+        tiles,
+        player_spawns: vec![player],
+        enemy_spawns,
+        bomb_timer: BOMB_TIMER,
+        explosion_range: EXPLOSION_RANGE,
+        drops: DropTable::default(),
+        remote_bombs: false,
+        time_limit: DEFAULT_TIME_LIMIT,
+    }
+}
+
+/// 生成随机地图并设为随机地图模式的当前关卡
+pub fn load_random_level(seed: u64, index: usize, levels: &mut Assets<LevelData>, handles: &mut LevelHandles) {
+    info!("generating random map, seed {}", seed);
+    handles.generated = Some(levels.add(generate(seed, &MapGenConfig::for_level(index))));
+}
+
+/// 按权重随机选择敌人类型（游走者最多，坦克最少）
+fn random_enemy(rng: &mut impl Rng) -> EnemyKind {
+    match rng.gen_range(0..8) {
+        0..=3 => EnemyKind::Wanderer,
+        4 | 5 => EnemyKind::Chaser,
+        6 => EnemyKind::Ghost,
+        _ => EnemyKind::Tank,
+    }
+}
+
+/// 从 start 出发能走到的所有格子
+fn reachable_from(
+    start: GridPosition,
+    width: usize,
+    height: usize,
+    passable: impl Fn(GridPosition) -> bool,
+) -> HashSet<GridPosition> {
+    let mut visited = HashSet::from_iter([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(current) = queue.pop_front() {
+        for next in current.neighbours() {
+            if in_bounds(next, width, height) && passable(next) && visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    visited
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_map() {
+        for stage in 0..4 {
+            let config = MapGenConfig::for_level(stage);
+            let (a, b) = (generate(42, &config), generate(42, &config));
+            assert_eq!(a.tiles, b.tiles);
+            assert_eq!(a.enemy_spawns, b.enemy_spawns);
+        }
+        // 同一个种子在不同关卡生成的地图不同（敌人数量不同）
+        assert_ne!(
+            generate(42, &MapGenConfig::for_level(0)).enemy_spawns.len(),
+            generate(42, &MapGenConfig::for_level(3)).enemy_spawns.len()
+        );
+    }
+
+    #[test]
+    fn seed_layout_is_pinned() {
+        let config = MapGenConfig { width: 9, height: 7, breakable_density: 0.5, enemies: 3 };
+        let level = generate(7, &config);
+        let rows: Vec<String> = (0..level.height as i32)
+            .map(|y| {
+                (0..level.width as i32)
+                    .map(|x| {
+                        let pos = GridPosition::new(x, y);
+                        if level.player_spawns.contains(&pos) {
+                            'P'
+                        } else if level.enemy_spawns.iter().any(|spawn| spawn.pos == pos) {
+                            'E'
+                        } else {
+                            match level.tile(x, y) {
+                                Tile::Wall => '#',
+                                Tile::BreakableWall => '*',
+                                Tile::Empty => '.',
+                            }
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        // 改动生成算法或随机数用法会让已分享的种子失效，需要有意识地更新这张图
+        assert_eq!(
+            rows,
+            [
+                "#########",
+                "#P...*.E#",
+                "#.#*#*#.#",
+                "#*.***.*#",
+                "#.#*#.#.#",
+                "#E.*...E#",
+                "#########",
+            ]
+        );
+    }
+
+    #[test]
+    fn every_enemy_is_reachable() {
+        for seed in 0..50 {
+            let config = MapGenConfig::for_level(seed as usize % 4);
+            let level = generate(seed, &config);
+            let player = level.player_spawns[0];
+            // 可破坏墙都能被炸开
+            let reachable = reachable_from(player, level.width, level.height, |pos| level.tile(pos.x, pos.y) != Tile::Wall);
+            assert_eq!(level.enemy_spawns.len(), config.enemies, "seed {seed}");
+            for spawn in level.enemy_spawns.iter() {
+                assert!(reachable.contains(&spawn.pos), "seed {seed}: enemy at {:?} is sealed off", spawn.pos);
+                assert_eq!(level.tile(spawn.pos.x, spawn.pos.y), Tile::Empty, "seed {seed}");
+            }
+        }
+    }
+}
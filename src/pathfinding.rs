@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+
+use bevy::utils::{HashMap, HashSet};
+use crate::{
+    components::{BombKind, GridPosition},
+    grid::blast_arms,
+};
+
+/// BFS 最多展开的格子数，防止大地图上单帧耗时过长
+const MAX_SEARCH_NODES: usize = 1024;
+
+/// 广度优先搜索，返回通往最近目标格子的第一步
+///
+/// 起点本身是目标时返回 None（无需移动）；找不到可达目标时也返回 None。
+pub fn bfs_first_step(
+    start: GridPosition,
+    is_goal: impl Fn(&GridPosition) -> bool,
+    is_passable: impl Fn(&GridPosition) -> bool,
+) -> Option<GridPosition> {
+    if is_goal(&start) {
+        return None;
+    }
+
+    let mut came_from: HashMap<GridPosition, GridPosition> = HashMap::default();
+    let mut queue = VecDeque::from([start]);
+    came_from.insert(start, start);
+
+    while let Some(current) = queue.pop_front() {
+        if came_from.len() > MAX_SEARCH_NODES {
+            break;
+        }
+
+        for next in current.neighbours() {
+            if came_from.contains_key(&next) || !is_passable(&next) {
+                continue;
+            }
+            came_from.insert(next, current);
+
+            if is_goal(&next) {
+                // 回溯到起点后的第一步
+                let mut step = next;
+                while came_from[&step] != start {
+                    step = came_from[&step];
+                }
+                return Some(step);
+            }
+            queue.push_back(next);
+        }
+    }
+
+    None
+}
+
+/// 计算所有待爆炸炸弹将覆盖的危险格子（十字型，遇墙停止，穿透炸弹只在不可破坏墙处停止）
+pub fn danger_tiles(
+    bombs: impl Iterator<Item = (GridPosition, i32, BombKind)>,
+    is_solid: impl Fn(&GridPosition) -> bool,
+    is_breakable: impl Fn(&GridPosition) -> bool,
+) -> HashSet<GridPosition> {
+    let mut tiles = HashSet::default();
+
+    for (center, range, kind) in bombs {
+        tiles.insert(center);
+        for (_, arm) in blast_arms(center, range) {
+            for pos in arm {
+                if is_solid(&pos) {
+                    break;
+                }
+                tiles.insert(pos);
+                if kind != BombKind::Pierce && is_breakable(&pos) {
+                    break;
+                }
+            }
+        }
+    }
+
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 用字符画描述的测试地图，越界视为不可破坏墙
+    struct Grid(&'static [&'static str]);
+
+    impl Grid {
+        fn at(&self, pos: &GridPosition) -> char {
+            let row = usize::try_from(pos.y).ok().and_then(|y| self.0.get(y));
+            row.and_then(|row| row.chars().nth(usize::try_from(pos.x).ok()?)).unwrap_or('#')
+        }
+
+        fn is_wall(&self, pos: &GridPosition) -> bool {
+            self.at(pos) == '#'
+        }
+
+        fn is_breakable(&self, pos: &GridPosition) -> bool {
+            self.at(pos) == '*'
+        }
+    }
+
+    fn row_tiles(tiles: &HashSet<GridPosition>) -> Vec<i32> {
+        let mut xs: Vec<i32> = tiles.iter().map(|pos| pos.x).collect();
+        xs.sort_unstable();
+        xs
+    }
+
+    #[test]
+    fn first_step_follows_shortest_path() {
+        let grid = Grid(&["#####", "#...#", "###.#", "#...#", "#####"]);
+        let target = GridPosition::new(1, 3);
+        let step = bfs_first_step(GridPosition::new(1, 1), |pos| *pos == target, |pos| !grid.is_wall(pos));
+        assert_eq!(step, Some(GridPosition::new(2, 1)));
+
+        // 已经在目标上时不需要移动
+        assert_eq!(bfs_first_step(target, |pos| *pos == target, |pos| !grid.is_wall(pos)), None);
+    }
+
+    #[test]
+    fn first_step_is_none_when_target_walled_off() {
+        let grid = Grid(&["#####", "#...#", "#####", "#...#", "#####"]);
+        let target = GridPosition::new(1, 3);
+        let step = bfs_first_step(GridPosition::new(1, 1), |pos| *pos == target, |pos| !grid.is_wall(pos));
+        assert_eq!(step, None);
+    }
+
+    #[test]
+    fn first_step_flees_to_nearest_safe_tile() {
+        let grid = Grid(&["#......#"]);
+        let danger = danger_tiles(
+            [(GridPosition::new(2, 0), 2, BombKind::Normal)].into_iter(),
+            |pos| grid.is_wall(pos),
+            |pos| grid.is_breakable(pos),
+        );
+        assert_eq!(row_tiles(&danger), vec![1, 2, 3, 4]);
+
+        let step = bfs_first_step(GridPosition::new(2, 0), |pos| !danger.contains(pos), |pos| !grid.is_wall(pos));
+        assert_eq!(step, Some(GridPosition::new(3, 0)));
+    }
+
+    #[test]
+    fn danger_stops_at_wall() {
+        let grid = Grid(&["...#..."]);
+        let danger = danger_tiles(
+            [(GridPosition::new(1, 0), 3, BombKind::Pierce)].into_iter(),
+            |pos| grid.is_wall(pos),
+            |pos| grid.is_breakable(pos),
+        );
+        assert_eq!(row_tiles(&danger), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn danger_stops_at_breakable_wall() {
+        let grid = Grid(&[".*..."]);
+        let danger = danger_tiles(
+            [(GridPosition::new(0, 0), 3, BombKind::Normal)].into_iter(),
+            |pos| grid.is_wall(pos),
+            |pos| grid.is_breakable(pos),
+        );
+        // 可破坏墙本身会被炸到
+        assert_eq!(row_tiles(&danger), vec![0, 1]);
+    }
+
+    #[test]
+    fn pierce_danger_passes_breakable_wall() {
+        let grid = Grid(&[".*.*.#."]);
+        let danger = danger_tiles(
+            [(GridPosition::new(0, 0), 6, BombKind::Pierce)].into_iter(),
+            |pos| grid.is_wall(pos),
+            |pos| grid.is_breakable(pos),
+        );
+        assert_eq!(row_tiles(&danger), vec![0, 1, 2, 3, 4]);
+    }
+}
@@ -1,9 +1,12 @@
 use bevy::prelude::*;
 use crate::{
+    animation::{animate_sprites, despawn_dead, ClipName, SpriteAnimation},
     components::*,
     constants::*,
-    map::grid_to_world,
-    resource::{GameState, GameTextures},
+    level::{ActiveLevel, LevelData},
+    grid::{cell_space_to_world, cells, grid_to_world, world_to_cell_space, world_to_grid},
+    resource::{GameMode, GameState, Lives},
+    tilemap::{Occupant, TileMap, TileMapSet},
 };
 
 pub struct PlayerPlugin;
@@ -12,91 +15,318 @@ impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_system(spawn_player.in_schedule(OnEnter(GameState::InGame)))
-            .add_system(player_movement.in_set(OnUpdate(GameState::InGame)));
+            .add_system(player_movement.in_set(OnUpdate(GameState::InGame)).in_set(TileMapSet::Move))
+            .add_system(lose_life.in_set(OnUpdate(GameState::InGame)))
+            // 在动画推进之后、尸体移除之前检查，保证能看到播完的死亡动画
+            .add_system(
+                respawn_players
+                    .in_set(OnUpdate(GameState::InGame))
+                    .after(animate_sprites)
+                    .before(despawn_dead),
+            )
+            .add_system(update_invulnerability.in_set(OnUpdate(GameState::InGame)));
     }
 }
 
-/// 生成玩家（使用TextureAtlasSprite）
-fn spawn_player(mut commands: Commands, game_textures: Res<GameTextures>) {
-    let start_pos = GridPosition::new(1, 1);
+/// 战役模式按键：WASD / 方向键移动，空格放炸弹，E / 右Shift 遥控引爆
+const CAMPAIGN_CONTROLS: PlayerControls = PlayerControls {
+    up: &[KeyCode::W, KeyCode::Up],
+    down: &[KeyCode::S, KeyCode::Down],
+    left: &[KeyCode::A, KeyCode::Left],
+    right: &[KeyCode::D, KeyCode::Right],
+    bomb: &[KeyCode::Space],
+    detonate: &[KeyCode::E, KeyCode::RShift],
+};
+
+/// 对战模式各玩家按键
+const VERSUS_CONTROLS: [PlayerControls; MAX_PLAYERS] = [
+    // 玩家1：WASD + 空格，E 引爆
+    PlayerControls {
+        up: &[KeyCode::W],
+        down: &[KeyCode::S],
+        left: &[KeyCode::A],
+        right: &[KeyCode::D],
+        bomb: &[KeyCode::Space],
+        detonate: &[KeyCode::E],
+    },
+    // 玩家2：方向键 + 回车，右Shift 引爆
+    PlayerControls {
+        up: &[KeyCode::Up],
+        down: &[KeyCode::Down],
+        left: &[KeyCode::Left],
+        right: &[KeyCode::Right],
+        bomb: &[KeyCode::Return],
+        detonate: &[KeyCode::RShift],
+    },
+    // 玩家3：IJKL + U，O 引爆
+    PlayerControls {
+        up: &[KeyCode::I],
+        down: &[KeyCode::K],
+        left: &[KeyCode::J],
+        right: &[KeyCode::L],
+        bomb: &[KeyCode::U],
+        detonate: &[KeyCode::O],
+    },
+    // 玩家4：小键盘 8456 + 0，小键盘 . 引爆
+    PlayerControls {
+        up: &[KeyCode::Numpad8],
+        down: &[KeyCode::Numpad5],
+        left: &[KeyCode::Numpad4],
+        right: &[KeyCode::Numpad6],
+        bomb: &[KeyCode::Numpad0],
+        detonate: &[KeyCode::NumpadDecimal],
+    },
+];
+
+/// 生成玩家，对战模式下每位玩家占据一个出生角落
+pub fn spawn_player(mut commands: Commands, game_mode: Res<GameMode>, level: ActiveLevel) {
+    let Some(level) = level.get() else {
+        return;
+    };
+
+    for id in 0..game_mode.players() {
+        // 出生点不够时循环使用
+        let start_pos = level.player_spawns[id % level.player_spawns.len()];
+        spawn_player_entity(&mut commands, &game_mode, level, id, start_pos);
+    }
+}
+
+/// 在指定格子生成一名玩家（复活时也使用，能力恢复为初始值）
+fn spawn_player_entity(
+    commands: &mut Commands,
+    game_mode: &GameMode,
+    level: &LevelData,
+    id: usize,
+    start_pos: GridPosition,
+) -> Entity {
+    let controls = match *game_mode {
+        GameMode::Campaign | GameMode::Random { .. } => CAMPAIGN_CONTROLS,
+        GameMode::Versus { .. } => VERSUS_CONTROLS[id],
+    };
     let mut world_pos = grid_to_world(start_pos.x, start_pos.y);
     world_pos.z = 10.0; // 玩家在最上层
 
-    commands.spawn((
-        SpriteSheetBundle {
-            texture_atlas: game_textures.player.clone(),
-            sprite: TextureAtlasSprite::new(43), // 使用第43帧（第4行第2列，玩家4动画）
-            transform: Transform::from_translation(world_pos).with_scale(Vec3::splat(3.5)),
-            ..default()
-        },
+    let mut player = commands.spawn((
+        TransformBundle::from_transform(Transform::from_translation(world_pos).with_scale(Vec3::splat(3.5))),
+        // 每位玩家使用图集中不同的角色行，第1列为朝下站立帧
+        SpriteFrame::new(Sheet::Player, PLAYER_SPRITE_ROWS[id] * 14 + 1),
         Player,
+        SpriteAnimation::character(PLAYER_SPRITE_ROWS[id], 1),
+        PlayerId(id),
+        controls,
+        Facing::default(),
         start_pos,
         Speed(PLAYER_SPEED),
+        BombCapacity::new(INITIAL_BOMB_CAPACITY),
+        BlastRange(level.explosion_range),
     ));
+
+    // 遥控关卡：所有玩家开局即拥有遥控引爆能力
+    if level.remote_bombs {
+        player.insert(RemoteDetonator);
+    }
+
+    player.id()
+}
+
+/// 玩家被击中时扣除一条生命
+fn lose_life(mut lives: ResMut<Lives>, query: Query<&PlayerId, (With<Player>, Added<Dying>)>) {
+    for id in query.iter() {
+        if let Some(remaining) = lives.0.get_mut(id.0) {
+            *remaining = remaining.saturating_sub(1);
+        }
+    }
+}
+
+/// 死亡动画播完后，还有剩余生命的玩家在出生点复活，并获得一段闪烁的无敌时间
+///
+/// 缩圈后出生点可能已被墙封住，此时改在离出生点最近的空地复活；
+/// 整个场地都被封住时无处复活，玩家失去剩余的所有生命（本局随之结束）。
+#[allow(clippy::type_complexity)]
+fn respawn_players(
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    mut lives: ResMut<Lives>,
+    level: ActiveLevel,
+    query: Query<(&PlayerId, &SpriteAnimation), (With<Player>, With<Dying>)>,
+    tile_map: Res<TileMap>,
+) {
+    let Some(level) = level.get() else {
+        return;
+    };
+
+    for (id, animation) in query.iter() {
+        // 死亡的实体随后由 despawn_dead 在同一帧移除
+        if animation.current() != ClipName::Death || !animation.finished() || lives.get(id.0) == 0 {
+            continue;
+        }
+        let spawn = level.player_spawns[id.0 % level.player_spawns.len()];
+        let Some(start_pos) = nearest_free_cell(spawn, level, &tile_map) else {
+            lives.0[id.0] = 0;
+            continue;
+        };
+        let entity = spawn_player_entity(&mut commands, &game_mode, level, id.0, start_pos);
+        commands
+            .entity(entity)
+            .insert(Invulnerable::new(INVULNERABILITY_DURATION, INVULNERABILITY_BLINK_INTERVAL));
+    }
+}
+
+/// 离 from 最近（曼哈顿距离）的非墙格子
+fn nearest_free_cell(from: GridPosition, level: &LevelData, tile_map: &TileMap) -> Option<GridPosition> {
+    cells(level.width, level.height)
+        .filter(|cell| !tile_map.is_solid(*cell))
+        .min_by_key(|cell| cell.manhattan(from))
+}
+
+/// 无敌时间结束后移除无敌状态（闪烁由 SpritesPlugin 显示）
+fn update_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable), Without<Stop>>,
+) {
+    for (entity, mut invulnerable) in query.iter_mut() {
+        if invulnerable.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
 }
 
-/// 玩家移动系统
+/// 玩家移动系统：一次只沿一个轴移动，稍微偏离通道中心时自动滑入对齐
+#[allow(clippy::type_complexity)]
 fn player_movement(
     keyboard: Res<Input<KeyCode>>,
     time: Res<Time>,
-    mut player_query: Query<(&mut Transform, &mut GridPosition, &Speed), (With<Player>, Without<Stop>)>,
-    wall_query: Query<&GridPosition, (Or<(With<Wall>, With<BreakableWall>)>, Without<Player>)>,
+    mut player_query: Query<
+        (Entity, &mut Transform, &mut GridPosition, &mut Facing, &mut SpriteAnimation, &Speed, &PlayerControls),
+        (With<Player>, Without<Stop>, Without<Dying>)
+    >,
+    tile_map: Res<TileMap>,
+    bomb_query: Query<&Bomb>,
 ) {
-    if let Ok((mut transform, mut grid_pos, speed)) = player_query.get_single_mut() {
-        let mut direction = Vec2::ZERO;
-
-        // 检测按键（支持长按）
-        if keyboard.pressed(KeyCode::W) || keyboard.pressed(KeyCode::Up) {
-            direction.y = 1.0;
-        } else if keyboard.pressed(KeyCode::S) || keyboard.pressed(KeyCode::Down) {
-            direction.y = -1.0;
-        }
-        
-        if keyboard.pressed(KeyCode::A) || keyboard.pressed(KeyCode::Left) {
-            direction.x = -1.0;
-        } else if keyboard.pressed(KeyCode::D) || keyboard.pressed(KeyCode::Right) {
-            direction.x = 1.0;
+    for (entity, mut transform, mut grid_pos, mut facing, mut animation, speed, controls) in player_query.iter_mut() {
+        let (horizontal, vertical) = input_axes(&keyboard, controls);
+
+        // 每帧移动的格子数
+        let step = speed.0 * time.delta_seconds();
+        let center = world_to_cell_space(transform.translation);
+
+        // 墙体和炸弹都会阻挡玩家，但放置时站在上面的炸弹在离开前可以穿过
+        let blocked = |pos: &GridPosition| {
+            tile_map.is_solid(*pos)
+                || tile_map
+                    .entities(*pos, Occupant::Bomb)
+                    .any(|bomb| bomb_query.get(bomb).map_or(false, |bomb| !bomb.pass_through.contains(&entity)))
+        };
+
+        // 同时按下两个方向时，优先尝试能走通的那个轴，不会斜向移动
+        let candidates = [IVec2::new(0, vertical), IVec2::new(horizontal, 0)];
+        let mut pressed = candidates.into_iter().filter(|dir| *dir != IVec2::ZERO).peekable();
+        let Some(&first) = pressed.peek() else {
+            animation.play(ClipName::Idle);
+            continue;
+        };
+        let moved = pressed.find_map(|dir| try_move(center, dir, step, &blocked).map(|c| (dir, c)));
+
+        // 面朝实际移动的方向；被完全挡住时面朝第一个按下的方向
+        let dir = moved.map_or(first, |(dir, _)| dir);
+        *facing = Facing { x: dir.x, y: dir.y };
+
+        // 根据实际移动方向播放行走动画，被挡住时站立
+        animation.play(if moved.is_some() { ClipName::walk(dir.x, dir.y) } else { ClipName::Idle });
+
+        if let Some((_, new_center)) = moved {
+            transform.translation = cell_space_to_world(new_center, transform.translation.z);
+            // 更新网格位置（取玩家中心所在的格子）
+            *grid_pos = world_to_grid(transform.translation);
         }
+    }
+}
+
+/// 读取玩家按住的方向（支持长按），返回 (水平, 垂直)，网格坐标系中 y 向下为正
+pub fn input_axes(keyboard: &Input<KeyCode>, controls: &PlayerControls) -> (i32, i32) {
+    let mut vertical = 0;
+    if PlayerControls::pressed(keyboard, controls.up) {
+        vertical = -1;
+    } else if PlayerControls::pressed(keyboard, controls.down) {
+        vertical = 1;
+    }
+
+    let mut horizontal = 0;
+    if PlayerControls::pressed(keyboard, controls.left) {
+        horizontal = -1;
+    } else if PlayerControls::pressed(keyboard, controls.right) {
+        horizontal = 1;
+    }
+
+    (horizontal, vertical)
+}
+
+/// 沿一个轴移动玩家，返回移动后的中心（格子坐标），无法移动时返回 None
+fn try_move(center: Vec2, dir: IVec2, step: f32, blocked: &impl Fn(&GridPosition) -> bool) -> Option<Vec2> {
+    let dir_f = dir.as_vec2();
+    let target = center + dir_f * step;
+    if !is_blocked(target, blocked) {
+        return Some(target);
+    }
 
-        if direction != Vec2::ZERO {
-            // 计算移动
-            //move_delta = 方向 × 速度 × 帧间隔 × 格子大小    //乘以格子大小转换为像素
-            let move_delta = direction * speed.0 * time.delta_seconds() * CELL_SIZE;
-            let new_pos = transform.translation + Vec3::new(move_delta.x, move_delta.y, 0.0);
-            
-            // 计算新的网格位置
-            let new_grid = world_to_grid(new_pos);
-            
-            // 检查新位置是否可通过（只检查墙体，不检查炸弹）
-            if !is_blocked(&new_grid, &wall_query) {
-                // 更新网格位置和实际位置
-                *grid_pos = new_grid;
-                transform.translation = new_pos;
+    // 转角辅助：偏离通道中心不远且对齐后前方可通行时，先向通道中心滑动
+    let lateral_axis = if dir.x != 0 { Vec2::Y } else { Vec2::X };
+    let lateral = center.dot(lateral_axis);
+    let offset = lateral - lateral.round();
+    if offset.abs() > f32::EPSILON && offset.abs() <= CORNER_ASSIST {
+        let aligned = center - lateral_axis * offset;
+        if !is_blocked(aligned + dir_f * step, blocked) {
+            let slide = center - lateral_axis * offset.signum() * step.min(offset.abs());
+            if !is_blocked(slide, blocked) {
+                return Some(slide);
             }
         }
     }
-}
 
-/// 检查位置是否被阻挡（只检查墙体）
-fn is_blocked(
-    pos: &GridPosition,
-    wall_query: &Query<&GridPosition, (Or<(With<Wall>, With<BreakableWall>)>, Without<Player>)>,
-) -> bool {
-    // 只检查墙体，炸弹不阻挡玩家
-    for wall_pos in wall_query.iter() {
-        if wall_pos == pos {
-            return true;
+    // 前方被挡住：贴着墙停下（碰撞框边缘与格子边缘对齐）
+    let along_axis = dir_f.abs();
+    let along = center.dot(along_axis);
+    let sign = dir_f.dot(along_axis);
+    let limit = along.round() + sign * (0.5 - player_half_extent());
+    if (limit - along) * sign > f32::EPSILON {
+        let clamped = center + along_axis * (limit - along);
+        if !is_blocked(clamped, blocked) {
+            return Some(clamped);
         }
     }
-    
-    false
+
+    None
+}
+
+/// 玩家碰撞框的半边长（以格子为单位）
+fn player_half_extent() -> f32 {
+    PLAYER_SIZE / CELL_SIZE / 2.0
+}
+
+/// 检查玩家碰撞框是否与任何阻挡格子重叠
+fn is_blocked(center: Vec2, blocked: &impl Fn(&GridPosition) -> bool) -> bool {
+    covered_cells(center).any(|pos| blocked(&pos))
+}
+
+/// 玩家碰撞框覆盖的所有格子
+fn covered_cells(center: Vec2) -> impl Iterator<Item = GridPosition> {
+    // 稍微缩小碰撞框，使刚好贴边不算重叠
+    let half = player_half_extent() - 1e-3;
+    let min = ((center - half) + 0.5).floor();
+    let max = ((center + half) + 0.5).floor();
+
+    (min.x as i32..=max.x as i32)
+        .flat_map(move |x| (min.y as i32..=max.y as i32).map(move |y| GridPosition::new(x, y)))
+}
+
+/// 玩家沿指定方向再走一小步是否会碰到指定格子（用于判断玩家正在推挤该格子）
+pub fn pushes_against(translation: Vec3, direction: (i32, i32), cell: GridPosition) -> bool {
+    let center = world_to_cell_space(translation) + IVec2::new(direction.0, direction.1).as_vec2() * 0.05;
+    covered_cells(center).any(|pos| pos == cell)
 }
 
-/// 世界坐标转网格坐标
-fn world_to_grid(pos: Vec3) -> GridPosition {
-    let offset = -(GRID_SIZE as f32 * CELL_SIZE) / 2.0 + CELL_SIZE / 2.0;
-    GridPosition::new(
-        ((pos.x - offset) / CELL_SIZE).round() as i32,
-        ((-pos.y - offset) / CELL_SIZE).round() as i32,
-    )
+/// 玩家（世界坐标）的碰撞框是否与指定格子重叠
+pub fn overlaps_cell(translation: Vec3, cell: GridPosition) -> bool {
+    covered_cells(world_to_cell_space(translation)).any(|pos| pos == cell)
 }
@@ -0,0 +1,104 @@
+use bevy::{prelude::*, utils::HashSet};
+use crate::{
+    components::*,
+    constants::*,
+    grid::grid_to_world,
+    resource::GameState,
+    tilemap::{Occupant, TileMap, TileMapSet},
+};
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(pick_up_power_ups.in_set(OnUpdate(GameState::InGame)).after(TileMapSet::Sync));
+    }
+}
+
+/// 生成道具（外观由 SpritesPlugin 挂上）
+pub fn spawn_power_up(commands: &mut Commands, pos: &GridPosition, kind: PowerUp) {
+    let mut world_pos = grid_to_world(pos.x, pos.y);
+    world_pos.z = 2.0; // 道具在炸弹下方
+
+    commands.spawn((TransformBundle::from_transform(Transform::from_translation(world_pos)), kind, *pos));
+}
+
+/// 玩家拾取道具，提升对应属性（同一帧多名玩家站在同一格时只有第一个拿到）
+#[allow(clippy::type_complexity)]
+fn pick_up_power_ups(
+    mut commands: Commands,
+    tile_map: Res<TileMap>,
+    power_up_query: Query<&PowerUp>,
+    mut player_query: Query<
+        (Entity, &GridPosition, &mut BombCapacity, &mut BlastRange, &mut Speed),
+        (With<Player>, Without<Stop>, Without<Dying>)
+    >,
+) {
+    // 本帧已被拾取的道具，despawn 要到帧末才生效
+    let mut picked: HashSet<Entity> = HashSet::new();
+
+    for (player_entity, player_pos, mut capacity, mut range, mut speed) in player_query.iter_mut() {
+        for power_up_entity in tile_map.entities(*player_pos, Occupant::PowerUp) {
+            let Ok(power_up) = power_up_query.get(power_up_entity) else {
+                continue;
+            };
+            if !picked.insert(power_up_entity) {
+                continue;
+            }
+
+            match power_up {
+                PowerUp::BombUp => {
+                    if capacity.max < MAX_BOMB_CAPACITY {
+                        capacity.max += 1;
+                        capacity.available += 1;
+                    }
+                }
+                PowerUp::FireUp => range.0 = (range.0 + 1).min(MAX_BLAST_RANGE),
+                PowerUp::SpeedUp => speed.0 = (speed.0 + SPEED_UP_AMOUNT).min(MAX_PLAYER_SPEED),
+                PowerUp::Kick => {
+                    commands.entity(player_entity).insert(KickAbility);
+                }
+                PowerUp::Remote => {
+                    commands.entity(player_entity).insert(RemoteDetonator);
+                }
+                PowerUp::Pierce => {
+                    commands.entity(player_entity).insert(PierceBombs);
+                }
+                PowerUp::LineBomb => {
+                    commands.entity(player_entity).insert(LineBomb);
+                }
+            }
+
+            commands.entity(power_up_entity).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_up_goes_to_one_player_only() {
+        let mut app = App::new();
+        let pos = GridPosition::new(1, 1);
+        let power_up = app.world.spawn((PowerUp::FireUp, pos)).id();
+        let mut tile_map = TileMap::new(3, 3);
+        tile_map.insert(power_up, pos, Occupant::PowerUp);
+        app.insert_resource(tile_map).add_system(pick_up_power_ups);
+
+        let players: Vec<Entity> = (0..2)
+            .map(|_| {
+                app.world
+                    .spawn((Player, pos, BombCapacity::new(1), BlastRange(1), Speed(PLAYER_SPEED)))
+                    .id()
+            })
+            .collect();
+        app.update();
+
+        let ranges: Vec<i32> = players.iter().map(|&player| app.world.get::<BlastRange>(player).unwrap().0).collect();
+        assert_eq!(ranges.iter().filter(|&&range| range == 2).count(), 1);
+        assert_eq!(ranges.iter().sum::<i32>(), 3);
+        assert!(app.world.get_entity(power_up).is_none());
+    }
+}
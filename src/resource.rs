@@ -1,7 +1,15 @@
 use bevy::prelude::*;
+#[cfg(feature = "graphics")]
 use bevy::sprite::TextureAtlas;
+use rand::{rngs::StdRng, SeedableRng};
+use crate::{
+    constants::{STARTING_LIVES, VERSUS_WINS_NEEDED},
+    components::GridPosition,
+    level::LevelData,
+};
 
 /// 游戏纹理资源（使用TextureAtlas精灵图集）
+#[cfg(feature = "graphics")]
 #[derive(Resource)]
 pub struct GameTextures {
     pub player: Handle<TextureAtlas>,
@@ -13,6 +21,7 @@ pub struct GameTextures {
 }
 
 /// 游戏音频资源
+#[cfg(feature = "graphics")]
 #[derive(Resource)]
 pub struct GameAudio {
     pub game_over: Handle<AudioSource>,
@@ -21,6 +30,131 @@ pub struct GameAudio {
     pub bomb_place: Handle<AudioSource>,
     pub victory: Handle<AudioSource>,
     pub bomb_explosion: Handle<AudioSource>,
+    pub music: Handle<AudioSource>,
+}
+
+/// 正在播放的关卡背景音乐
+#[cfg(feature = "graphics")]
+#[derive(Resource, Default)]
+pub struct BackgroundMusic(pub Option<Handle<AudioSink>>);
+
+/// 关卡数据句柄
+#[derive(Resource)]
+pub struct LevelHandles {
+    /// 战役关卡（按游玩顺序）
+    pub campaign: Vec<Handle<LevelData>>,
+    /// 对战地图
+    pub versus: Handle<LevelData>,
+    /// 随机地图模式当前生成的地图
+    pub generated: Option<Handle<LevelData>>,
+}
+
+/// 命令行 `--seed <n> [--stage <n>]` 指定的随机地图（用于重玩别人分享的地图，只对第一次开始随机地图模式生效）
+///
+/// 随机地图由种子和关卡序号共同决定（关卡越往后敌人越多），分享时两者都要给出。
+#[derive(Resource, Default)]
+pub struct SeedArg {
+    pub seed: Option<u64>,
+    /// 关卡序号（从0开始）
+    pub stage: usize,
+}
+
+/// 当前关卡序号（从0开始）
+#[derive(Resource, Default)]
+pub struct CurrentLevel(pub usize);
+
+/// 游戏模式
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameMode {
+    /// 单人战役
+    #[default]
+    Campaign,
+    /// 本地多人对战（2~4人）
+    Versus { players: usize },
+    /// 单人随机地图：按种子生成地图，过关后换一个新种子
+    Random { seed: u64 },
+}
+
+impl GameMode {
+    /// 本模式的玩家人数
+    pub fn players(&self) -> usize {
+        match *self {
+            Self::Campaign | Self::Random { .. } => 1,
+            Self::Versus { players } => players,
+        }
+    }
+}
+
+/// 对战比分（N局多胜制）
+#[derive(Resource, Default)]
+pub struct MatchScore {
+    /// 每位玩家赢得的局数
+    pub wins: Vec<u32>,
+    /// 上一局的胜者（None 表示平局）
+    pub round_winner: Option<usize>,
+}
+
+impl MatchScore {
+    pub fn new(players: usize) -> Self {
+        Self {
+            wins: vec![0; players],
+            round_winner: None,
+        }
+    }
+
+    /// 已赢得整场比赛的玩家
+    pub fn match_winner(&self) -> Option<usize> {
+        self.wins.iter().position(|wins| *wins >= VERSUS_WINS_NEEDED)
+    }
+}
+
+/// 战役得分
+#[derive(Resource, Default)]
+pub struct Score {
+    pub total: u32,
+    /// 进入本关时的得分（重新挑战时恢复到该值）
+    pub level_start: u32,
+    /// 上一次过关获得的时间奖励
+    pub time_bonus: u32,
+}
+
+/// 本关已进行的时间（暂停时不计时）和时间限制
+#[derive(Resource, Default)]
+pub struct LevelTimer {
+    pub elapsed: f32,
+    pub limit: f32,
+    /// 是否已进入 HURRY UP 阶段
+    pub hurry_up: bool,
+}
+
+impl LevelTimer {
+    /// 剩余时间（秒）
+    pub fn remaining(&self) -> f32 {
+        (self.limit - self.elapsed).max(0.0)
+    }
+}
+
+/// 时间耗尽后的缩圈进度：按螺旋顺序逐格落下不可破坏墙
+#[derive(Resource)]
+pub struct ClosingArena {
+    pub cells: Vec<GridPosition>,
+    pub next: usize,
+    pub timer: Timer,
+}
+
+/// 每位玩家剩余的生命数（按 PlayerId 索引，死亡时扣除）
+#[derive(Resource, Default)]
+pub struct Lives(pub Vec<u32>);
+
+impl Lives {
+    pub fn new(players: usize) -> Self {
+        Self(vec![STARTING_LIVES; players])
+    }
+
+    /// 玩家剩余生命数（越界视为0）
+    pub fn get(&self, id: usize) -> u32 {
+        self.0.get(id).copied().unwrap_or(0)
+    }
 }
 
 /// 游戏状态
@@ -32,6 +166,8 @@ pub enum GameState {
     Paused,
     Victory,
     GameOver,
+    CampaignComplete,
+    RoundOver,
 }
 
 /// 游戏结束延迟计时器
@@ -44,3 +180,21 @@ pub struct GameOverDelay {
 /// 游戏暂停标记，不采用状态
 #[derive(Resource, Default)]
 pub struct GamePaused(pub bool);
+
+/// 玩法中所有随机数的来源（敌人游走方向、道具掉落）
+///
+/// 窗口模式用系统熵初始化；无窗口模拟按局的种子初始化，同样的种子和输入得到同样的结果。
+#[derive(Resource)]
+pub struct SimRng(pub StdRng);
+
+impl SimRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for SimRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
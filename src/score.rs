@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+use crate::resource::{GamePaused, GameState, LevelTimer, Score};
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system(start_level.in_schedule(OnEnter(GameState::InGame)))
+            .add_system(tick_level_timer.in_set(OnUpdate(GameState::InGame)));
+    }
+}
+
+/// 进入关卡：记录本关起始得分，重新计时
+fn start_level(mut score: ResMut<Score>, mut level_timer: ResMut<LevelTimer>) {
+    score.level_start = score.total;
+    score.time_bonus = 0;
+    level_timer.elapsed = 0.0;
+}
+
+/// 关卡计时（暂停时不计时）
+fn tick_level_timer(time: Res<Time>, game_paused: Res<GamePaused>, mut level_timer: ResMut<LevelTimer>) {
+    if !game_paused.0 {
+        level_timer.elapsed += time.delta_seconds();
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(feature = "graphics")]
+use bevy::{audio::AudioSinkPlayback, prelude::*};
+#[cfg(feature = "graphics")]
+use crate::{
+    constants::*,
+    resource::{BackgroundMusic, GameAudio, GameState, LevelTimer},
+};
+
+/// 玩法中的音效
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sound {
+    BombPlace,
+    BombExplosion,
+    EnemyExplosion,
+    PlayerExplosion,
+}
+
+/// 播放音效的请求：玩法系统只发送事件，由 SoundPlugin 播放；无窗口模拟中没有读者，事件会被自动丢弃
+#[derive(Clone, Copy, Debug)]
+pub struct SoundEvent {
+    pub sound: Sound,
+    pub volume: f32,
+}
+
+impl SoundEvent {
+    pub fn new(sound: Sound, volume: f32) -> Self {
+        Self { sound, volume }
+    }
+}
+
+/// 播放音效和关卡背景音乐（HURRY UP 时加速）
+#[cfg(feature = "graphics")]
+pub struct SoundPlugin;
+
+#[cfg(feature = "graphics")]
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<SoundEvent>()
+            .init_resource::<BackgroundMusic>()
+            .add_system(start_music.in_schedule(OnEnter(GameState::InGame)))
+            .add_system(stop_music.in_schedule(OnExit(GameState::InGame)))
+            .add_system(hurry_up_music.in_set(OnUpdate(GameState::InGame)))
+            .add_system(play_sounds);
+    }
+}
+
+#[cfg(feature = "graphics")]
+fn play_sounds(mut events: EventReader<SoundEvent>, game_audio: Res<GameAudio>, audio: Res<Audio>) {
+    for event in events.iter() {
+        let source = match event.sound {
+            Sound::BombPlace => &game_audio.bomb_place,
+            Sound::BombExplosion => &game_audio.bomb_explosion,
+            Sound::EnemyExplosion => &game_audio.enemy_explosion,
+            Sound::PlayerExplosion => &game_audio.player_explosion,
+        };
+        audio.play_with_settings(
+            source.clone(),
+            PlaybackSettings {
+                repeat: false,
+                volume: event.volume,
+                speed: 1.0,
+            },
+        );
+    }
+}
+
+/// 进入关卡时播放背景音乐
+#[cfg(feature = "graphics")]
+fn start_music(
+    mut music: ResMut<BackgroundMusic>,
+    game_audio: Res<GameAudio>,
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+) {
+    let sink = audio.play_with_settings(
+        game_audio.music.clone(),
+        PlaybackSettings {
+            repeat: true,
+            volume: 0.1,
+            speed: 1.0,
+        },
+    );
+    music.0 = Some(audio_sinks.get_handle(sink));
+}
+
+/// 离开关卡时停止背景音乐
+#[cfg(feature = "graphics")]
+fn stop_music(mut music: ResMut<BackgroundMusic>, audio_sinks: Res<Assets<AudioSink>>) {
+    if let Some(sink) = music.0.take().and_then(|handle| audio_sinks.get(&handle)) {
+        sink.stop();
+    }
+}
+
+/// 进入 HURRY UP 后音乐加速
+#[cfg(feature = "graphics")]
+fn hurry_up_music(level_timer: Res<LevelTimer>, music: Res<BackgroundMusic>, audio_sinks: Res<Assets<AudioSink>>) {
+    if !level_timer.hurry_up {
+        return;
+    }
+    if let Some(sink) = music.0.as_ref().and_then(|handle| audio_sinks.get(handle)) {
+        if sink.speed() != HURRY_UP_MUSIC_SPEED {
+            sink.set_speed(HURRY_UP_MUSIC_SPEED);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use bevy::{prelude::*, render::texture::DEFAULT_IMAGE_HANDLE};
+use crate::{
+    components::*,
+    constants::*,
+    resource::GameTextures,
+};
+
+/// 给玩法实体挂上精灵：图集帧、敌人着色、道具方块和无敌闪烁
+pub struct SpritesPlugin;
+
+impl Plugin for SpritesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system(attach_sprites.in_base_set(CoreSet::PostUpdate))
+            .add_system(sync_sprite_frames.in_base_set(CoreSet::PostUpdate))
+            .add_system(attach_power_ups.in_base_set(CoreSet::PostUpdate))
+            .add_system(blink_invulnerable.in_base_set(CoreSet::PostUpdate));
+    }
+}
+
+/// 敌人精灵的着色
+fn enemy_tint(kind: EnemyKind) -> Color {
+    match kind {
+        EnemyKind::Wanderer => Color::WHITE,
+        EnemyKind::Chaser => Color::rgb(1.0, 0.6, 0.6),
+        EnemyKind::Ghost => Color::rgba(0.7, 0.8, 1.0, 0.6),
+        EnemyKind::Tank => Color::rgb(0.6, 0.6, 0.6),
+    }
+}
+
+/// 道具的底色和标识字母
+fn power_up_style(kind: PowerUp) -> (Color, &'static str) {
+    match kind {
+        PowerUp::BombUp => (Color::rgb(0.2, 0.2, 0.2), "B"),
+        PowerUp::FireUp => (Color::rgb(0.9, 0.3, 0.1), "F"),
+        PowerUp::SpeedUp => (Color::rgb(0.2, 0.6, 1.0), "S"),
+        PowerUp::Kick => (Color::rgb(0.3, 0.8, 0.3), "K"),
+        PowerUp::Remote => (Color::rgb(0.7, 0.3, 0.9), "R"),
+        PowerUp::Pierce => (Color::rgb(0.9, 0.7, 0.1), "P"),
+        PowerUp::LineBomb => (Color::rgb(0.1, 0.7, 0.7), "L"),
+    }
+}
+
+/// 新生成的实体按 SpriteFrame 使用对应的精灵图集（TextureAtlasSprite）
+fn attach_sprites(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    query: Query<(Entity, &SpriteFrame, Option<&EnemyKind>), Added<SpriteFrame>>,
+) {
+    for (entity, frame, kind) in query.iter() {
+        let texture_atlas = match frame.sheet {
+            Sheet::Player => &game_textures.player,
+            Sheet::Enemy => &game_textures.enemy,
+            Sheet::Wall => &game_textures.wall,
+            Sheet::Bomb => &game_textures.bomb,
+            Sheet::Fire => &game_textures.fire,
+        };
+        commands.entity(entity).insert((
+            texture_atlas.clone(),
+            TextureAtlasSprite {
+                index: frame.index,
+                color: kind.map_or(Color::WHITE, |kind| enemy_tint(*kind)),
+                ..default()
+            },
+            VisibilityBundle::default(),
+        ));
+    }
+}
+
+/// 动画和爆炸推进帧号后同步到精灵
+fn sync_sprite_frames(mut query: Query<(&SpriteFrame, &mut TextureAtlasSprite), Changed<SpriteFrame>>) {
+    for (frame, mut sprite) in query.iter_mut() {
+        if sprite.index != frame.index {
+            sprite.index = frame.index;
+        }
+    }
+}
+
+/// 新露出的道具显示为彩色方块 + 标识字母
+fn attach_power_ups(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    query: Query<(Entity, &PowerUp), Added<PowerUp>>,
+) {
+    for (entity, kind) in query.iter() {
+        let (color, label) = power_up_style(*kind);
+        commands
+            .entity(entity)
+            .insert((
+                Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(POWER_UP_SIZE)),
+                    ..default()
+                },
+                DEFAULT_IMAGE_HANDLE.typed::<Image>(),
+                VisibilityBundle::default(),
+            ))
+            .with_children(|parent| {
+                parent.spawn(Text2dBundle {
+                    text: Text::from_section(
+                        label,
+                        TextStyle {
+                            font: game_textures.font.clone(),
+                            font_size: 36.0,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_alignment(TextAlignment::Center),
+                    transform: Transform::from_xyz(0.0, 0.0, 0.1),
+                    ..default()
+                });
+            });
+    }
+}
+
+/// 无敌时间内每个闪烁间隔切换一次显示，结束后恢复显示
+fn blink_invulnerable(
+    mut query: Query<(&Invulnerable, &mut Visibility)>,
+    mut ended: RemovedComponents<Invulnerable>,
+    mut visibility_query: Query<&mut Visibility, Without<Invulnerable>>,
+) {
+    for (invulnerable, mut visibility) in query.iter_mut() {
+        let blinks = (invulnerable.timer.elapsed_secs() / invulnerable.blink_interval) as u32;
+        let wanted = if blinks % 2 == 1 { Visibility::Hidden } else { Visibility::Inherited };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+    }
+    for entity in ended.iter() {
+        if let Ok(mut visibility) = visibility_query.get_mut(entity) {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use bevy::{
+    ecs::{
+        query::WorldQuery,
+        schedule::{apply_state_transition, apply_system_buffers},
+    },
+    prelude::*,
+    utils::HashMap,
+};
+use crate::{
+    components::*,
+    grid::in_bounds,
+    level::{ActiveLevel, Tile},
+    resource::GameState,
+};
+
+pub struct TileMapPlugin;
+
+impl Plugin for TileMapPlugin {
+    fn build(&self, app: &mut App) {
+        // 在状态切换之后、游戏系统之前同步，进入关卡时生成的墙体在第一帧就能查到；
+        // 关卡中移动和生成实体的系统之后再同步一次，同一帧的结算系统读到的是移动后的占用
+        app
+            .init_resource::<TileMap>()
+            .add_system(
+                sync_tile_map
+                    .in_base_set(CoreSet::StateTransitions)
+                    .after(apply_state_transition::<GameState>),
+            )
+            .configure_set(TileMapSet::Sync.after(TileMapSet::Move))
+            .add_systems(
+                (apply_system_buffers, sync_tile_map)
+                    .chain()
+                    .in_set(TileMapSet::Sync)
+                    .in_set(OnUpdate(GameState::InGame)),
+            );
+    }
+}
+
+/// 占用表的同步点
+///
+/// 移动实体（修改 GridPosition）或生成、移除带 GridPosition 实体的系统放在 Move 中；
+/// 按格子结算爆炸、碰撞和拾取的系统排在 Sync 之后。
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TileMapSet {
+    Move,
+    Sync,
+}
+
+/// 格子中实体的类别
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Occupant {
+    Wall,
+    BreakableWall,
+    Bomb,
+    Player,
+    Enemy,
+    PowerUp,
+    Explosion,
+    /// 其他带 GridPosition 的实体
+    Other,
+}
+
+/// 一个格子：地形和站在上面的实体
+#[derive(Clone, Debug, Default)]
+pub struct Cell {
+    pub tile: Tile,
+    pub occupants: Vec<(Entity, Occupant)>,
+}
+
+impl Cell {
+    /// 根据格子中的墙体实体重新计算地形
+    fn update_tile(&mut self) {
+        self.tile = if self.occupants.iter().any(|(_, kind)| *kind == Occupant::Wall) {
+            Tile::Wall
+        } else if self.occupants.iter().any(|(_, kind)| *kind == Occupant::BreakableWall) {
+            Tile::BreakableWall
+        } else {
+            Tile::Empty
+        };
+    }
+}
+
+/// 网格占用表：按格子记录地形和占用实体，由 sync_tile_map 随实体生成、移动和移除保持同步
+///
+/// 通行判断只需按下标查表，不再逐个遍历墙体和炸弹实体。
+#[derive(Resource, Debug, Default)]
+pub struct TileMap {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    /// 每个实体当前登记的格子和类别，用于移动和移除时找到原来的格子
+    entities: HashMap<Entity, (GridPosition, Occupant)>,
+}
+
+impl TileMap {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+            entities: HashMap::new(),
+        }
+    }
+
+    fn index(&self, pos: GridPosition) -> Option<usize> {
+        if !in_bounds(pos, self.width, self.height) {
+            return None;
+        }
+        Some(pos.y as usize * self.width + pos.x as usize)
+    }
+
+    /// 指定格子的地形（越界视为不可破坏墙）
+    pub fn tile(&self, pos: GridPosition) -> Tile {
+        self.index(pos).map_or(Tile::Wall, |index| self.cells[index].tile)
+    }
+
+    /// 是否是墙（可破坏或不可破坏）
+    pub fn is_solid(&self, pos: GridPosition) -> bool {
+        self.tile(pos) != Tile::Empty
+    }
+
+    /// 格子中的所有实体（越界为空）
+    pub fn occupants(&self, pos: GridPosition) -> &[(Entity, Occupant)] {
+        self.index(pos).map_or(&[], |index| &self.cells[index].occupants)
+    }
+
+    /// 格子中指定类别的实体
+    pub fn entities(&self, pos: GridPosition, kind: Occupant) -> impl Iterator<Item = Entity> + '_ {
+        self.occupants(pos)
+            .iter()
+            .filter(move |(_, occupant)| *occupant == kind)
+            .map(|(entity, _)| *entity)
+    }
+
+    /// 格子中第一个指定类别的实体
+    pub fn find(&self, pos: GridPosition, kind: Occupant) -> Option<Entity> {
+        self.entities(pos, kind).next()
+    }
+
+    /// 格子中是否有指定类别的实体
+    pub fn contains(&self, pos: GridPosition, kind: Occupant) -> bool {
+        self.find(pos, kind).is_some()
+    }
+
+    /// 登记实体到格子（已登记的实体先从原来的格子移除）
+    pub fn insert(&mut self, entity: Entity, pos: GridPosition, kind: Occupant) {
+        self.remove(entity);
+        // 越界的实体不登记
+        let Some(index) = self.index(pos) else {
+            return;
+        };
+        let cell = &mut self.cells[index];
+        cell.occupants.push((entity, kind));
+        if matches!(kind, Occupant::Wall | Occupant::BreakableWall) {
+            cell.update_tile();
+        }
+        self.entities.insert(entity, (pos, kind));
+    }
+
+    /// 从所在格子移除实体
+    pub fn remove(&mut self, entity: Entity) {
+        let Some((pos, kind)) = self.entities.remove(&entity) else {
+            return;
+        };
+        let Some(index) = self.index(pos) else {
+            return;
+        };
+        let cell = &mut self.cells[index];
+        cell.occupants.retain(|(occupant, _)| *occupant != entity);
+        if matches!(kind, Occupant::Wall | Occupant::BreakableWall) {
+            cell.update_tile();
+        }
+    }
+}
+
+/// 带网格位置的实体及其标记组件
+#[derive(WorldQuery)]
+pub struct GridEntity {
+    entity: Entity,
+    pos: &'static GridPosition,
+    wall: Option<&'static Wall>,
+    breakable: Option<&'static BreakableWall>,
+    bomb: Option<&'static Bomb>,
+    player: Option<&'static Player>,
+    enemy: Option<&'static Enemy>,
+    power_up: Option<&'static PowerUp>,
+    explosion: Option<&'static Explosion>,
+}
+
+impl GridEntityItem<'_> {
+    /// 根据实体带有的标记组件确定类别
+    fn kind(&self) -> Occupant {
+        if self.wall.is_some() {
+            Occupant::Wall
+        } else if self.breakable.is_some() {
+            Occupant::BreakableWall
+        } else if self.bomb.is_some() {
+            Occupant::Bomb
+        } else if self.player.is_some() {
+            Occupant::Player
+        } else if self.enemy.is_some() {
+            Occupant::Enemy
+        } else if self.power_up.is_some() {
+            Occupant::PowerUp
+        } else if self.explosion.is_some() {
+            Occupant::Explosion
+        } else {
+            Occupant::Other
+        }
+    }
+}
+
+/// 同步网格占用表：处理上一帧移除的实体和新增、移动的实体
+///
+/// 地图尺寸随关卡变化时整表重建。
+pub fn sync_tile_map(
+    mut tile_map: ResMut<TileMap>,
+    level: ActiveLevel,
+    mut removed: RemovedComponents<GridPosition>,
+    changed_query: Query<GridEntity, Changed<GridPosition>>,
+    all_query: Query<GridEntity>,
+) {
+    let (width, height) = level.get().map_or((0, 0), |level| (level.width, level.height));
+    let rebuild = tile_map.width != width || tile_map.height != height;
+    if rebuild {
+        *tile_map = TileMap::new(width, height);
+    }
+
+    for entity in removed.iter() {
+        tile_map.remove(entity);
+    }
+
+    let mut register = |item: GridEntityItem| tile_map.insert(item.entity, *item.pos, item.kind());
+    if rebuild {
+        all_query.iter().for_each(&mut register);
+    } else {
+        changed_query.iter().for_each(&mut register);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{asset::AssetPlugin, core::TaskPoolPlugin};
+    use super::*;
+    use crate::{
+        level::{parse_level, LevelData, LevelPlugin},
+        resource::{CurrentLevel, GameMode, LevelHandles},
+    };
+
+    #[test]
+    fn insert_and_remove_update_tile() {
+        let mut tile_map = TileMap::new(3, 3);
+        let pos = GridPosition::new(1, 1);
+        let (breakable, wall) = (Entity::from_raw(0), Entity::from_raw(1));
+
+        tile_map.insert(breakable, pos, Occupant::BreakableWall);
+        assert_eq!(tile_map.tile(pos), Tile::BreakableWall);
+        tile_map.insert(wall, pos, Occupant::Wall);
+        assert_eq!(tile_map.tile(pos), Tile::Wall);
+
+        tile_map.remove(wall);
+        assert_eq!(tile_map.tile(pos), Tile::BreakableWall);
+        tile_map.remove(breakable);
+        assert_eq!(tile_map.tile(pos), Tile::Empty);
+        assert!(tile_map.occupants(pos).is_empty());
+    }
+
+    #[test]
+    fn insert_moves_registered_entity() {
+        let mut tile_map = TileMap::new(3, 3);
+        let bomb = Entity::from_raw(0);
+        tile_map.insert(bomb, GridPosition::new(0, 0), Occupant::Bomb);
+        tile_map.insert(bomb, GridPosition::new(2, 1), Occupant::Bomb);
+
+        assert!(!tile_map.contains(GridPosition::new(0, 0), Occupant::Bomb));
+        assert_eq!(tile_map.find(GridPosition::new(2, 1), Occupant::Bomb), Some(bomb));
+
+        // 越界的格子视为墙，移到越界位置的实体不再登记
+        tile_map.insert(bomb, GridPosition::new(3, 0), Occupant::Bomb);
+        assert!(tile_map.is_solid(GridPosition::new(3, 0)));
+        assert!(!tile_map.contains(GridPosition::new(2, 1), Occupant::Bomb));
+    }
+
+    /// 只运行 sync_tile_map 的应用，当前关卡为 5x3 的空地图
+    fn sync_app() -> App {
+        let mut app = App::new();
+        app.add_plugin(TaskPoolPlugin::default())
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(LevelPlugin)
+            .init_resource::<CurrentLevel>()
+            .init_resource::<TileMap>()
+            .insert_resource(GameMode::Versus { players: 1 })
+            .add_system(sync_tile_map);
+        let level = parse_level("mode = versus\n[map]\n.....\n..P..\n.....\n").unwrap();
+        let versus = app.world.resource_mut::<Assets<LevelData>>().add(level);
+        app.insert_resource(LevelHandles { campaign: Vec::new(), versus, generated: None });
+        app
+    }
+
+    #[test]
+    fn sync_follows_spawn_move_and_despawn() {
+        let mut app = sync_app();
+        let wall = app.world.spawn((Wall, GridPosition::new(1, 1))).id();
+        let player = app.world.spawn((Player, GridPosition::new(2, 1))).id();
+        app.update();
+
+        let tile_map = app.world.resource::<TileMap>();
+        assert_eq!(tile_map.tile(GridPosition::new(1, 1)), Tile::Wall);
+        assert_eq!(tile_map.find(GridPosition::new(2, 1), Occupant::Player), Some(player));
+
+        *app.world.get_mut::<GridPosition>(player).unwrap() = GridPosition::new(3, 1);
+        app.world.despawn(wall);
+        app.update();
+
+        let tile_map = app.world.resource::<TileMap>();
+        assert_eq!(tile_map.tile(GridPosition::new(1, 1)), Tile::Empty);
+        assert!(tile_map.occupants(GridPosition::new(2, 1)).is_empty());
+        assert_eq!(tile_map.find(GridPosition::new(3, 1), Occupant::Player), Some(player));
+    }
+}
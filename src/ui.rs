@@ -2,7 +2,9 @@ use bevy::prelude::*;
 use crate::{
     components::*,
     constants::*,
-    resource::{GameState, GameTextures, GameAudio, GamePaused},
+    level::{ActiveLevel, LevelData},
+    mapgen::{load_random_level, new_seed},
+    resource::{GameState, GameTextures, GameAudio, GamePaused, CurrentLevel, LevelHandles, GameMode, MatchScore, Lives, Score, LevelTimer, SeedArg},
 };
 
 pub struct UIPlugin;
@@ -15,6 +17,10 @@ impl Plugin for UIPlugin {
             .add_system(setup_welcome_ui.in_schedule(OnEnter(GameState::Welcome)))
             .add_system(welcome_input.in_set(OnUpdate(GameState::Welcome)))
             .add_system(cleanup_welcome_ui.in_schedule(OnExit(GameState::Welcome)))
+            // HUD
+            .add_system(setup_hud.in_schedule(OnEnter(GameState::InGame)))
+            .add_system(update_hud.in_set(OnUpdate(GameState::InGame)))
+            .add_system(cleanup_hud.in_schedule(OnExit(GameState::InGame)))
             // Paused (使用资源标记，不切换状态)
             .add_system(handle_pause_toggle.in_set(OnUpdate(GameState::InGame)))
             .add_system(update_pause_ui.in_set(OnUpdate(GameState::InGame)).after(handle_pause_toggle))
@@ -27,7 +33,17 @@ impl Plugin for UIPlugin {
             .add_system(cleanup_game_entities.in_schedule(OnEnter(GameState::Victory))) // 进入胜利界面时清理游戏实体
             .add_system(setup_victory_ui.in_schedule(OnEnter(GameState::Victory)))
             .add_system(victory_input.in_set(OnUpdate(GameState::Victory)))
-            .add_system(cleanup_victory_ui.in_schedule(OnExit(GameState::Victory)));
+            .add_system(cleanup_victory_ui.in_schedule(OnExit(GameState::Victory)))
+            // Campaign complete screen
+            .add_system(cleanup_game_entities.in_schedule(OnEnter(GameState::CampaignComplete)))
+            .add_system(setup_campaign_complete_ui.in_schedule(OnEnter(GameState::CampaignComplete)))
+            .add_system(campaign_complete_input.in_set(OnUpdate(GameState::CampaignComplete)))
+            .add_system(cleanup_campaign_complete_ui.in_schedule(OnExit(GameState::CampaignComplete)))
+            // Versus round over / scoreboard screen
+            .add_system(cleanup_game_entities.in_schedule(OnEnter(GameState::RoundOver)))
+            .add_system(setup_round_over_ui.in_schedule(OnEnter(GameState::RoundOver)))
+            .add_system(round_over_input.in_set(OnUpdate(GameState::RoundOver)))
+            .add_system(cleanup_round_over_ui.in_schedule(OnExit(GameState::RoundOver)));
     }
 }
 
@@ -47,6 +63,8 @@ fn setup_welcome_ui(mut commands: Commands, game_textures: Res<GameTextures>) {
                 TextSection::new("WASD / Arrow Keys - Move\n", TextStyle { font_size: 60.0, ..text_style.clone() }),
                 TextSection::new("SPACE - Place Bomb\n", TextStyle { font_size: 60.0, ..text_style.clone() }),
                 TextSection::new("P - Pause Game\n\n", TextStyle { font_size: 60.0, ..text_style.clone() }),
+                TextSection::new("R - Random Map\n", TextStyle { font_size: 60.0, ..text_style.clone() }),
+                TextSection::new("2-4 - Versus Mode\n", TextStyle { font_size: 60.0, ..text_style.clone() }),
                 TextSection::new("Press ENTER to Start", TextStyle { 
                     font_size: 60.0,
                     color: Color::rgb(1.0, 0.8, 0.0), 
@@ -60,11 +78,55 @@ fn setup_welcome_ui(mut commands: Commands, game_textures: Res<GameTextures>) {
     ));
 }
 
+#[allow(clippy::too_many_arguments)]
 fn welcome_input(
     keyboard: Res<Input<KeyCode>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut game_mode: ResMut<GameMode>,
+    mut match_score: ResMut<MatchScore>,
+    mut lives: ResMut<Lives>,
+    mut score: ResMut<Score>,
+    mut level_assets: ResMut<Assets<LevelData>>,
+    mut level_handles: ResMut<LevelHandles>,
+    mut seed_arg: ResMut<SeedArg>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if keyboard.just_pressed(KeyCode::Return) {
+    // 随机地图：第一次使用命令行指定的种子，之后每次随机
+    if keyboard.just_pressed(KeyCode::R) {
+        let (seed, stage) = match seed_arg.seed.take() {
+            Some(seed) => (seed, seed_arg.stage),
+            None => (new_seed(), 0),
+        };
+        load_random_level(seed, stage, &mut level_assets, &mut level_handles);
+        *game_mode = GameMode::Random { seed };
+        *lives = Lives::new(1);
+        *score = Score::default();
+        current_level.0 = stage;
+        next_state.set(GameState::InGame);
+        return;
+    }
+
+    // 战役从第一关开始，关卡文件加载完成后才能开始游戏
+    let first_loaded = level_handles.campaign.first().and_then(|handle| level_assets.get(handle)).is_some();
+    if keyboard.just_pressed(KeyCode::Return) && first_loaded {
+        *game_mode = GameMode::Campaign;
+        *lives = Lives::new(1);
+        *score = Score::default();
+        current_level.0 = 0;
+        next_state.set(GameState::InGame);
+        return;
+    }
+
+    // 按 2/3/4 开始对应人数的对战
+    let versus_loaded = level_assets.get(&level_handles.versus).is_some();
+    let players = [(KeyCode::Key2, 2), (KeyCode::Key3, 3), (KeyCode::Key4, 4)]
+        .into_iter()
+        .find(|(key, _)| keyboard.just_pressed(*key))
+        .map(|(_, players)| players);
+    if let (Some(players), true) = (players, versus_loaded) {
+        *game_mode = GameMode::Versus { players };
+        *match_score = MatchScore::new(players);
+        *lives = Lives::new(players);
         next_state.set(GameState::InGame);
     }
 }
@@ -78,7 +140,125 @@ fn cleanup_welcome_ui(
     }
 }
 
+/// 游戏内HUD（网格上方的顶部状态栏）
+fn setup_hud(mut commands: Commands, game_textures: Res<GameTextures>) {
+    let bar_y = WINDOW_HEIGHT / 2.0 - HUD_HEIGHT / 2.0;
+
+    // 状态栏背景
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgba(0.0, 0.0, 0.0, 0.8),
+                custom_size: Some(Vec2::new(WINDOW_WIDTH, HUD_HEIGHT)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, bar_y, 49.0),
+            ..default()
+        },
+        ScreenSpace(Vec2::new(0.0, bar_y)),
+        HudUI,
+    ));
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: game_textures.font.clone(),
+                    font_size: 22.0,
+                    color: Color::WHITE,
+                },
+            ),
+            text_anchor: bevy::sprite::Anchor::CenterLeft,
+            transform: Transform::from_xyz(-WINDOW_WIDTH / 2.0 + 20.0, bar_y, 50.0),
+            ..default()
+        },
+        ScreenSpace(Vec2::new(-WINDOW_WIDTH / 2.0 + 20.0, bar_y)),
+        HudText,
+        HudUI,
+    ));
+}
+
+/// 更新HUD：得分、关卡、剩余时间，以及每位玩家的生命、炸弹数量和爆炸范围
+#[allow(clippy::type_complexity)]
+fn update_hud(
+    player_query: Query<(&PlayerId, &BombCapacity, &BlastRange), (With<Player>, Without<Dying>)>,
+    game_mode: Res<GameMode>,
+    lives: Res<Lives>,
+    score: Res<Score>,
+    level_timer: Res<LevelTimer>,
+    level: ActiveLevel,
+    mut hud_query: Query<&mut Text, With<HudText>>,
+) {
+    let Ok(mut text) = hud_query.get_single_mut() else {
+        return;
+    };
+
+    let seconds = level_timer.remaining().ceil() as u32;
+    let mut time = format!("TIME {:02}:{:02}", seconds / 60, seconds % 60);
+    if level_timer.hurry_up {
+        time.push_str("  HURRY UP!");
+    }
+
+    // 死亡或复活途中的玩家没有炸弹数据
+    let stats = |id: usize| {
+        player_query
+            .iter()
+            .find(|(player_id, _, _)| player_id.0 == id)
+            .map(|(_, capacity, range)| (format!("{}/{}", capacity.available, capacity.max), range.0.to_string()))
+            .unwrap_or_else(|| ("-".to_owned(), "-".to_owned()))
+    };
+
+    text.sections[0].value = match *game_mode {
+        GameMode::Campaign => {
+            let (bombs, range) = stats(0);
+            format!(
+                "SCORE {:06}   LEVEL {}   {}\nLIVES {}   BOMBS {}   RANGE {}",
+                score.total,
+                level.index() + 1,
+                time,
+                lives.get(0),
+                bombs,
+                range,
+            )
+        }
+        GameMode::Random { seed } => {
+            let (bombs, range) = stats(0);
+            format!(
+                "SCORE {:06}   STAGE {}   {}\nLIVES {}   BOMBS {}   RANGE {}   SEED {}",
+                score.total,
+                level.index() + 1,
+                time,
+                lives.get(0),
+                bombs,
+                range,
+                seed,
+            )
+        }
+        GameMode::Versus { players } => {
+            let players = (0..players)
+                .map(|id| {
+                    let (bombs, range) = stats(id);
+                    format!("P{} L{} B{} R{}", id + 1, lives.get(id), bombs, range)
+                })
+                .collect::<Vec<_>>()
+                .join("   ");
+            format!("VERSUS   {}\n{}", time, players)
+        }
+    };
+}
+
+fn cleanup_hud(
+    mut commands: Commands,
+    query: Query<Entity, With<HudUI>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 /// 处理暂停切换（在 InGame 状态下按 P 或 R 键）
+#[allow(clippy::too_many_arguments)]
 fn handle_pause_toggle(
     keyboard: Res<Input<KeyCode>>,
     mut game_paused: ResMut<GamePaused>,
@@ -165,6 +345,7 @@ fn update_pause_ui(
                 transform: Transform::from_xyz(0.0, 0.0, 100.0),
                 ..default()
             },
+            ScreenSpace::default(),
             DimOverlay,
             PausedUI,
         ));
@@ -185,6 +366,7 @@ fn update_pause_ui(
                 transform: Transform::from_xyz(0.0, 0.0, 101.0),
                 ..default()
             },
+            ScreenSpace::default(),
             PausedUI,
         ));
     } 
@@ -197,7 +379,13 @@ fn update_pause_ui(
 }
 
 /// Game over screen
-fn setup_gameover_ui(mut commands: Commands, game_textures: Res<GameTextures>, game_audio: Res<GameAudio>, audio: Res<Audio>) {
+fn setup_gameover_ui(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    game_audio: Res<GameAudio>,
+    audio: Res<Audio>,
+    score: Res<Score>,
+) {
     let text_style = TextStyle {
         font: game_textures.font.clone(),
         font_size: 90.0,
@@ -208,7 +396,10 @@ fn setup_gameover_ui(mut commands: Commands, game_textures: Res<GameTextures>, g
         Text2dBundle {
             text: Text::from_sections([
                 TextSection::new("GAME OVER!\n\n", text_style.clone()),
-                TextSection::new("You were blown up\n\n", TextStyle { font_size: 60.0, color: Color::BLACK, ..text_style.clone() }),
+                TextSection::new(
+                    format!("You were blown up\nScore {}\n\n", score.total),
+                    TextStyle { font_size: 60.0, color: Color::BLACK, ..text_style.clone() },
+                ),
                 TextSection::new("ENTER - Try Again", TextStyle { 
                     font_size: 60.0,
                     color: Color::rgb(0.8, 0.8, 0.8), 
@@ -234,9 +425,14 @@ fn setup_gameover_ui(mut commands: Commands, game_textures: Res<GameTextures>, g
 
 fn gameover_input(
     keyboard: Res<Input<KeyCode>>,
+    mut lives: ResMut<Lives>,
+    mut score: ResMut<Score>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
+    // 重新挑战当前关卡，生命恢复，得分回到进入本关时
     if keyboard.just_pressed(KeyCode::Return) {
+        *lives = Lives::new(1);
+        score.total = score.level_start;
         next_state.set(GameState::InGame);
     }
 }
@@ -250,12 +446,14 @@ fn cleanup_gameover_ui(
     }
 }
 
-/// Victory screen
+/// Victory screen（关卡通过）
 fn setup_victory_ui(
     mut commands: Commands, 
     game_textures: Res<GameTextures>, 
     game_audio: Res<GameAudio>, 
     audio: Res<Audio>,
+    level: ActiveLevel,
+    score: Res<Score>,
 ) {
     let text_style = TextStyle {
         font: game_textures.font.clone(),
@@ -267,8 +465,16 @@ fn setup_victory_ui(
         Text2dBundle {
             text: Text::from_sections([
                 TextSection::new("VICTORY!\n\n", text_style.clone()),
-                TextSection::new("All enemies defeated\n\n", TextStyle { font_size: 60.0, color: Color::BLACK, ..text_style.clone() }),
-                TextSection::new("ENTER - Play Again", TextStyle { 
+                TextSection::new(
+                    format!(
+                        "Level {} cleared\nTime bonus {}\nScore {}\n\n",
+                        level.index() + 1,
+                        score.time_bonus,
+                        score.total,
+                    ),
+                    TextStyle { font_size: 60.0, color: Color::BLACK, ..text_style.clone() },
+                ),
+                TextSection::new("ENTER - Next Level", TextStyle { 
                     font_size: 60.0,
                     color: Color::rgb(0.8, 0.8, 0.8), 
                     ..text_style 
@@ -293,9 +499,20 @@ fn setup_victory_ui(
 
 fn victory_input(
     keyboard: Res<Input<KeyCode>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut game_mode: ResMut<GameMode>,
+    mut level_assets: ResMut<Assets<LevelData>>,
+    mut level_handles: ResMut<LevelHandles>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
+    // 进入下一关（随机地图模式换一个新种子重新生成）
     if keyboard.just_pressed(KeyCode::Return) {
+        current_level.0 += 1;
+        if let GameMode::Random { .. } = *game_mode {
+            let seed = new_seed();
+            load_random_level(seed, current_level.0, &mut level_assets, &mut level_handles);
+            *game_mode = GameMode::Random { seed };
+        }
         next_state.set(GameState::InGame);
     }
 }
@@ -309,7 +526,157 @@ fn cleanup_victory_ui(
     }
 }
 
+/// Campaign complete screen（全部关卡通过）
+fn setup_campaign_complete_ui(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    game_audio: Res<GameAudio>,
+    audio: Res<Audio>,
+    level_handles: Res<LevelHandles>,
+    score: Res<Score>,
+) {
+    let text_style = TextStyle {
+        font: game_textures.font.clone(),
+        font_size: 90.0,
+        color: Color::rgb(1.0, 0.8, 0.0),
+    };
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_sections([
+                TextSection::new("CAMPAIGN\nCOMPLETE!\n\n", text_style.clone()),
+                TextSection::new(
+                    format!("All {} levels cleared\nFinal score {}\n\n", level_handles.campaign.len(), score.total),
+                    TextStyle { font_size: 60.0, color: Color::BLACK, ..text_style.clone() },
+                ),
+                TextSection::new("ENTER - Back to Menu", TextStyle {
+                    font_size: 60.0,
+                    color: Color::rgb(0.8, 0.8, 0.8),
+                    ..text_style
+                }),
+            ]).with_alignment(TextAlignment::Center),
+            transform: Transform::from_xyz(0.0, 0.0, 10.0),
+            ..default()
+        },
+        CampaignCompleteUI,
+    ));
+
+    audio.play_with_settings(
+        game_audio.victory.clone(),
+        PlaybackSettings {
+            repeat: false,
+            volume: 0.2,
+            speed: 1.0,
+        },
+    );
+}
+
+fn campaign_complete_input(
+    keyboard: Res<Input<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard.just_pressed(KeyCode::Return) {
+        next_state.set(GameState::Welcome);
+    }
+}
+
+fn cleanup_campaign_complete_ui(
+    mut commands: Commands,
+    query: Query<Entity, With<CampaignCompleteUI>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Versus round over screen（本局结果和比分）
+fn setup_round_over_ui(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    game_audio: Res<GameAudio>,
+    audio: Res<Audio>,
+    match_score: Res<MatchScore>,
+) {
+    let text_style = TextStyle {
+        font: game_textures.font.clone(),
+        font_size: 90.0,
+        color: Color::rgb(1.0, 0.8, 0.0),
+    };
+
+    let headline = match (match_score.match_winner(), match_score.round_winner) {
+        (Some(winner), _) => format!("PLAYER {}\nWINS THE MATCH!\n\n", winner + 1),
+        (None, Some(winner)) => format!("PLAYER {}\nWINS THE ROUND\n\n", winner + 1),
+        (None, None) => "DRAW!\n\n".to_owned(),
+    };
+    let scoreboard: String = match_score
+        .wins
+        .iter()
+        .enumerate()
+        .map(|(id, wins)| format!("P{}  {} / {}\n", id + 1, wins, VERSUS_WINS_NEEDED))
+        .collect();
+    let prompt = if match_score.match_winner().is_some() {
+        "ENTER - Back to Menu"
+    } else {
+        "ENTER - Next Round"
+    };
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_sections([
+                TextSection::new(headline, text_style.clone()),
+                TextSection::new(scoreboard + "\n", TextStyle { font_size: 60.0, color: Color::BLACK, ..text_style.clone() }),
+                TextSection::new(prompt, TextStyle {
+                    font_size: 60.0,
+                    color: Color::rgb(0.8, 0.8, 0.8),
+                    ..text_style
+                }),
+            ]).with_alignment(TextAlignment::Center),
+            transform: Transform::from_xyz(0.0, 0.0, 10.0),
+            ..default()
+        },
+        RoundOverUI,
+    ));
+
+    audio.play_with_settings(
+        game_audio.victory.clone(),
+        PlaybackSettings {
+            repeat: false,
+            volume: 0.2,
+            speed: 1.0,
+        },
+    );
+}
+
+fn round_over_input(
+    keyboard: Res<Input<KeyCode>>,
+    match_score: Res<MatchScore>,
+    game_mode: Res<GameMode>,
+    mut lives: ResMut<Lives>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard.just_pressed(KeyCode::Return) {
+        // 有人赢得比赛则回到主菜单，否则开始下一局
+        if match_score.match_winner().is_some() {
+            next_state.set(GameState::Welcome);
+        } else {
+            // 每局开始时所有玩家生命恢复
+            *lives = Lives::new(game_mode.players());
+            next_state.set(GameState::InGame);
+        }
+    }
+}
+
+fn cleanup_round_over_ui(
+    mut commands: Commands,
+    query: Query<Entity, With<RoundOverUI>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 /// Clean up game entities when exiting InGame state
+#[allow(clippy::type_complexity)]
 fn cleanup_game_entities(
     mut commands: Commands,
     player_query: Query<Entity, With<Player>>,
@@ -317,6 +684,7 @@ fn cleanup_game_entities(
     bomb_query: Query<Entity, With<Bomb>>,
     explosion_query: Query<Entity, With<Explosion>>,
     wall_query: Query<Entity, Or<(With<Wall>, With<BreakableWall>)>>,
+    power_up_query: Query<Entity, With<PowerUp>>,
 ) {
     // Clean up all game entities
     for entity in player_query.iter() {
@@ -334,4 +702,7 @@ fn cleanup_game_entities(
     for entity in wall_query.iter() {
         commands.entity(entity).despawn_recursive();
     }
+    for entity in power_up_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 }